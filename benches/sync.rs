@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate criterion;
+extern crate gradle_sync;
+extern crate semver;
+
+use criterion::{Criterion, black_box};
+use semver::Version;
+use gradle_sync::{BuildGradleContent, ConfigurationFormat, PropertiesContent};
+use gradle_sync::parse_version_code_line;
+
+/// A build.gradle with `dependencies` dependency lines, comments and strings
+/// around the `defaultConfig` block.
+fn generate_build_gradle(dependencies: usize) -> String {
+    let mut content = String::from("// generated build file\nandroid {\n    defaultConfig {\n");
+    content.push_str("        versionCode 12\n        versionName \"1.2.3\"\n    }\n}\n\ndependencies {\n");
+    for index in 0..dependencies {
+        content.push_str(&format!(
+            "    implementation \"com.example:module{}:1.0.{}\" // versionCode {}\n",
+            index, index, index));
+        content.push_str("    /* versionName \"0.0.1\" */\n");
+    }
+    content.push_str("}\n");
+    content
+}
+
+fn generate_properties(properties: usize) -> String {
+    let mut content = String::new();
+    for index in 0..properties {
+        content.push_str(&format!("org.example.property{}=value{}\n", index, index));
+    }
+    content.push_str("projectVersion=1.2.3\n");
+    content
+}
+
+fn bench_build_gradle(c: &mut Criterion) {
+    let new_version = Version::parse("1.3.0").unwrap();
+    for &size in [100usize, 10_000].iter() {
+        let source = generate_build_gradle(size);
+        c.bench_function(&format!("build.gradle sync {} dependencies", size), |b| {
+            b.iter(|| {
+                let mut content = <BuildGradleContent as ConfigurationFormat>::from(source.as_bytes()).unwrap();
+                content.sync_version(&new_version).unwrap();
+                black_box(content.render())
+            })
+        });
+    }
+}
+
+fn bench_properties(c: &mut Criterion) {
+    let new_version = Version::parse("1.3.0").unwrap();
+    let source = generate_properties(10_000);
+    c.bench_function("gradle.properties sync 10000 properties", |b| {
+        b.iter(|| {
+            let mut content = <PropertiesContent as ConfigurationFormat>::from(source.as_bytes()).unwrap();
+            content.sync_version(&new_version).unwrap();
+            black_box(content.render())
+        })
+    });
+}
+
+fn bench_line_helpers(c: &mut Criterion) {
+    let source = generate_build_gradle(1_000);
+    c.bench_function("parse_version_code_line 1000 dependencies", |b| {
+        b.iter(|| {
+            black_box(source.lines().filter_map(parse_version_code_line).count())
+        })
+    });
+}
+
+criterion_group!(benches, bench_build_gradle, bench_properties, bench_line_helpers);
+criterion_main!(benches);
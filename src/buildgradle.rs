@@ -1,47 +1,45 @@
 use semver::Version;
-use regex::{Regex, Captures};
 use configfile::ConfigurationFormat;
 use error::GradleResult;
 use std::io::Read;
-use std::io::{BufReader, BufRead};
+use std::ops::Range;
 use error::Error;
-use version::GradleVersion;
+use version::{AbiSplits, GradleVersion, SyncAction, SyncOptions, MAX_VERSION_CODE};
+use span::{VersionSpans, read_source};
+use groovy::{tokenize, find_statements, find_map_entries, primary_statement, line_number, Statement, Token, TokenKind};
 
 pub struct BuildGradleContent {
-    lines: Vec<String>,
+    source: String,
+    spans: VersionSpans,
+    abi_codes: Vec<(String, Range<usize>)>,
+    abi_multipliers: Vec<Range<usize>>,
+    abi_splits: Option<AbiSplits>,
     version: GradleVersion,
     modified: bool
 }
 
 impl ConfigurationFormat for BuildGradleContent {
     fn from<R: Read>(reader: R) -> GradleResult<Self>{
-        let mut version_code: Option<u32> = None;
-        let mut version_name: Option<Version> = None;
-
-        let f = BufReader::new(reader);
-        let mut lines: Vec<String> = vec!();
-        for result_line in f.lines() {
-            let line = result_line?;
-            if version_code.is_none() {
-                version_code = parse_version_code_line(&line);
-            }
-            if version_name.is_none() {
-                version_name = parse_version_name_line(&line);
-            }
-            lines.push(line);
-        }
-        if version_code.is_none() {
-            return Err(Error::VersionNotFound("failed to find versionCode".to_string()))
-        }
-        if version_name.is_none() {
-            return Err(Error::VersionNotFound("failed to find versionName".to_string()))
-        }
+        let source = read_source(reader)?;
+        let (version, spans) = parse_script(&source, "GString")?;
+        let tokens = tokenize(&source);
+        let abi_codes = find_map_entries(&source, &tokens, "abiCodes").into_iter()
+            .map(|entry| (entry.key, entry.value.start..entry.value.end))
+            .collect();
+        let mut abi_multipliers: Vec<Range<usize>> = version_code_statements(find_statements(&source, &tokens, "abiCodeMultiplier"))
+            .into_iter()
+            .map(|statement| statement.value.start..statement.value.end)
+            .chain(abi_code_factors(&source, &tokens))
+            .collect();
+        abi_multipliers.sort_by_key(|range| range.start);
+        abi_multipliers.dedup();
         Ok(Self{
-            lines,
-            version: GradleVersion::new(
-                         version_code.unwrap(),
-                         version_name.unwrap()
-                         ),
+            source,
+            spans,
+            abi_codes,
+            abi_multipliers,
+            abi_splits: None,
+            version,
             modified: false
         })
     }
@@ -51,70 +49,185 @@ impl ConfigurationFormat for BuildGradleContent {
     fn is_modified(&self) -> bool {
         self.modified
     }
-    fn sync_version(&mut self, new_version: &Version) -> GradleResult<()> {
-        if self.version.synchronize_version(new_version)? {
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
             self.modified = true
         }
-        Ok(())
+        if let Some(abi_splits) = options.code_strategy.abi_splits() {
+            if self.abi_edits(abi_splits).iter().any(|edit| self.source[edit.0.clone()] != edit.1) {
+                self.modified = true
+            }
+            self.abi_splits = Some(abi_splits.clone());
+        }
+        Ok(action)
     }
-    fn lines(&self) -> Vec<String> {
-        self.lines.iter().map(|line|{
-            let line = replace_version_code(line.clone(), self.version.code());
-            let line = replace_version_name(line, self.version.version());
-            line
-        }).collect()
+    fn render(&self) -> String {
+        let extra = match self.abi_splits {
+            Some(ref abi_splits) => self.abi_edits(abi_splits),
+            None => vec!(),
+        };
+        self.spans.render_with(&self.source, &self.version, &extra)
     }
 }
 
-pub fn parse_version_code_line(line: &str) -> Option<u32> {
-    let re = Regex::new(r"versionCode\s+(?P<code>\d+)").unwrap();
-    let caps = re.captures(line);
-    match caps {
-        Some(caps) => {
-            let code: u32 = (&caps["code"]).parse().unwrap();
+impl BuildGradleContent {
+    /// The edits that align the `abiCodes` map and the multiplier with the
+    /// configured ABI splits. The multiplier is either an `abiCodeMultiplier`
+    /// variable or the `N` of a `versionCode * N + abiCodes[...]` expression.
+    fn abi_edits(&self, abi_splits: &AbiSplits) -> Vec<(Range<usize>, String)> {
+        self.abi_codes.iter()
+            .filter_map(|(abi, range)| {
+                abi_splits.index(abi).map(|index| (range.clone(), index.to_string()))
+            })
+            .chain(self.abi_multipliers.iter()
+                .map(|range| (range.clone(), abi_splits.multiplier.to_string())))
+            .collect()
+    }
+}
+
+/// Finds the literal `N` of `versionCode * N + abiCodes...` expressions,
+/// i.e. the last `* N` before a reference to `abiCodes` on the same line.
+fn abi_code_factors(source: &str, tokens: &[Token]) -> Vec<Range<usize>> {
+    tokens.iter().enumerate()
+        .filter(|&(_index, token)| token.kind == TokenKind::Identifier && token.text(source) == "abiCodes")
+        .filter_map(|(index, _token)| {
+            let line: Vec<&Token> = tokens[..index].iter().rev()
+                .take_while(|token| token.kind != TokenKind::Newline)
+                .collect();
+            line.windows(2)
+                .find(|pair| pair[0].kind == TokenKind::Number && pair[1].kind == TokenKind::Symbol('*'))
+                .map(|pair| pair[0].start..pair[0].end)
+        })
+        .collect()
+}
+
+/// Finds the versionCode and versionName statements of a Groovy or Kotlin
+/// build script. `interpolation` names the interpolated string kind of the
+/// language in error messages.
+pub(crate) fn parse_script(source: &str, interpolation: &str) -> GradleResult<(GradleVersion, VersionSpans)> {
+    let tokens = tokenize(source);
+
+    let statements = find_statements(source, &tokens, "versionName");
+    if let Some(statement) = statements.iter().find(|statement| statement.value.is_unterminated()) {
+        let reason = format!("versionName on line {} is an unterminated string",
+                             line_number(source, statement.value.start));
+        return Err(Error::ParsingFailed(reason))
+    }
+    let code_statements = version_code_statements(find_statements(source, &tokens, "versionCode"));
+    let name_statements = version_name_statements(source, statements.clone());
+    let version_code = match primary_statement(&code_statements) {
+        // A number too large for a u32 is named rather than reported missing.
+        Some(statement) if statement.value.text(source).bytes().all(|c| c.is_ascii_digit()) => {
+            let text = statement.value.text(source);
+            let code = text.parse().map_err(|_err| {
+                let reason = format!(
+                    "versionCode {} on line {} is outside the Google Play range 1..={}",
+                    text, line_number(source, statement.value.start), MAX_VERSION_CODE);
+                Error::InvalidVersionCode(reason)
+            })?;
             Some(code)
         },
-        None => None
+        Some(statement) => statement.value.text(source).parse().ok(),
+        None => None,
+    };
+    let version_name = primary_statement(&name_statements)
+        .and_then(|statement| Version::parse(&source[statement.value.content()]).ok());
+    if version_code.is_none() {
+        return Err(Error::VersionNotFound("failed to find versionCode".to_string()))
+    }
+    if version_name.is_none() {
+        if let Some(statement) = statements.iter().find(|statement| is_interpolated(statement)) {
+            let reason = format!(
+                "versionName on line {} is an interpolated {} ({}), \
+                 replace it with a literal version such as \"1.0.0\" to synchronize it",
+                line_number(source, statement.value.start),
+                interpolation,
+                statement.value.text(source)
+            );
+            return Err(Error::UnsupportedSyntax(reason))
+        }
+        return Err(Error::VersionNotFound("failed to find versionName".to_string()))
     }
+    // Only the statements in the block of the primary one are rewritten, so
+    // the overrides of product flavors and build types are left alone.
+    let code_blocks = &primary_statement(&code_statements).unwrap().blocks;
+    let name_blocks = &primary_statement(&name_statements).unwrap().blocks;
+    let spans = VersionSpans {
+        code: code_statements.iter()
+            .filter(|statement| &statement.blocks == code_blocks)
+            .map(|statement| statement.value.start..statement.value.end)
+            .collect(),
+        name: name_statements.iter()
+            .filter(|statement| &statement.blocks == name_blocks)
+            .map(|statement| statement.value.content())
+            .collect(),
+        short_name: false,
+    };
+    Ok((GradleVersion::new(version_code.unwrap(), version_name.unwrap()), spans))
 }
 
-pub fn parse_version_name_line(line: &str) -> Option<Version> {
-    let re = Regex::new(r#"versionName\s+"(?P<name>[\d.]+)""#).unwrap();
-    let caps = re.captures(line);
-    match caps {
-        Some(caps) => {
-            match Version::parse(&caps["name"]) {
-                Ok(version) => Some(version),
-                Err(_) => None
+fn version_code_statements(statements: Vec<Statement>) -> Vec<Statement> {
+    statements.into_iter()
+        .filter(|statement| statement.value.kind == TokenKind::Number)
+        .collect()
+}
+
+fn version_name_statements(source: &str, statements: Vec<Statement>) -> Vec<Statement> {
+    statements.into_iter()
+        .filter(|statement| {
+            match statement.value.kind {
+                TokenKind::String { interpolated: false, terminated: true, .. } => {
+                    Version::parse(&source[statement.value.content()]).is_ok()
+                },
+                _ => false
             }
-        },
-        None => None
+        })
+        .collect()
+}
+
+fn is_interpolated(statement: &Statement) -> bool {
+    match statement.value.kind {
+        TokenKind::String { interpolated, .. } => interpolated,
+        _ => false
     }
 }
 
+pub fn parse_version_code_line(line: &str) -> Option<u32> {
+    let tokens = tokenize(line);
+    version_code_statements(find_statements(line, &tokens, "versionCode"))
+        .first()
+        .and_then(|statement| statement.value.text(line).parse().ok())
+}
+
+pub fn parse_version_name_line(line: &str) -> Option<Version> {
+    let tokens = tokenize(line);
+    version_name_statements(line, find_statements(line, &tokens, "versionName"))
+        .first()
+        .and_then(|statement| Version::parse(&line[statement.value.content()]).ok())
+}
+
 pub fn replace_version_code(line: String, version_code: u32) -> String {
-    let old_version_code = parse_version_code_line(&line);
-    match old_version_code {
-        Some(_) => {
-            let re = Regex::new(r#"versionCode\s+\d+"#).unwrap();
-            let new_line = re.replace(&line, |_: &Captures| {
-                format!("versionCode {}", version_code)
-            });
-            new_line.to_string()
+    let tokens = tokenize(&line);
+    let statements = version_code_statements(find_statements(&line, &tokens, "versionCode"));
+    match statements.first() {
+        Some(statement) => {
+            let mut new_line = line.clone();
+            new_line.replace_range(statement.value.start..statement.value.end, &version_code.to_string());
+            new_line
         },
         None => line
     }
 }
 
 pub fn replace_version_name(line: String, version: &Version) -> String {
-    let old_version_name = parse_version_name_line(&line);
-    match old_version_name {
-        Some(_) => {
-            let re = Regex::new(r#"versionName\s+"[\d.]+""#).unwrap();
-            let new_line = re.replace(&line, |_: &Captures| {
-                format!(r#"versionName "{}""#, version.to_string())
-            });
-            new_line.to_string()
+    let tokens = tokenize(&line);
+    let statements = version_name_statements(&line, find_statements(&line, &tokens, "versionName"));
+    match statements.first() {
+        Some(statement) => {
+            let mut new_line = line.clone();
+            new_line.replace_range(statement.value.content(), &version.to_string());
+            new_line
         },
         None => line
     }
@@ -0,0 +1,48 @@
+use semver::Version;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+use buildgradle::parse_script;
+
+/// Kotlin DSL build script (`build.gradle.kts`).
+///
+/// Kotlin shares the comment and string syntax that matters here with
+/// Groovy, so the same scanner finds `versionCode = 5` and
+/// `versionName = "1.2.3"`.
+pub struct BuildGradleKtsContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for BuildGradleKtsContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let (version, spans) = parse_script(&source, "string template")?;
+        Ok(Self{
+            source,
+            spans,
+            version,
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
@@ -0,0 +1,36 @@
+use semver::Version;
+use regex::Regex;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use error::{Error, GradleResult};
+use version::sem_version_parse;
+use versioncatalog::parse_table_header;
+
+lazy_static! {
+    static ref PACKAGE_VERSION_RE: Regex = Regex::new(r#"^\s*version\s*=\s*"(?P<version>[^"]+)""#).unwrap();
+}
+
+/// Reads the `[package]` version from a `Cargo.toml` manifest.
+pub fn read_cargo_version(filename: &str) -> GradleResult<Version> {
+    let fd = File::open(filename)
+        .map_err(|_err| {
+            let reason = format!("failed to read file: {}", filename);
+            Error::IoError(reason)
+        })?;
+    parse_cargo_version(fd)
+}
+
+pub fn parse_cargo_version<R: Read>(reader: R) -> GradleResult<Version> {
+    let mut in_package = false;
+    for result_line in BufReader::new(reader).lines() {
+        let line = result_line?;
+        if let Some(table) = parse_table_header(&line) {
+            in_package = table == "package";
+        } else if in_package {
+            if let Some(caps) = PACKAGE_VERSION_RE.captures(&line) {
+                return sem_version_parse(&caps["version"])
+            }
+        }
+    }
+    Err(Error::VersionNotFound("failed to find the package version".to_string()))
+}
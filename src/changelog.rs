@@ -0,0 +1,185 @@
+//! Releasing the `[Unreleased]` section of a Keep a Changelog
+//! `CHANGELOG.md` when the version is bumped.
+
+use semver::Version;
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use error::{Error, GradleResult};
+use span::{read_source, line_offsets};
+
+lazy_static! {
+    static ref HEADING_RE: Regex = Regex::new(r"^##\s+\[?(?P<name>[^\]\s]+)\]?").unwrap();
+    static ref LINK_RE: Regex = Regex::new(r"^\[[^\]]+\]:\s").unwrap();
+    static ref UNRELEASED_LINK_RE: Regex = Regex::new(
+        r"^\[Unreleased\]:\s*(?P<base>\S+/compare/)(?P<from>\S+)\.\.\.(?P<to>\S+)$").unwrap();
+}
+
+/// What the changelog step does after a version bump.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ChangelogMode {
+    /// Move the `[Unreleased]` entries under a new version heading.
+    Release,
+    /// Only require a section for the new version, e.g. on CI.
+    Check,
+}
+
+pub struct Changelog {
+    filename: String,
+    source: String,
+    modified: bool
+}
+
+impl Changelog {
+    pub fn open(filename: &str) -> GradleResult<Changelog> {
+        let fd = File::open(filename)
+            .map_err(|_err| {
+                let reason = format!("failed to read file: {}", filename);
+                Error::IoError(reason)
+            })?;
+        Changelog::from_reader(filename, fd)
+    }
+
+    pub fn from_reader<R: Read>(filename: &str, reader: R) -> GradleResult<Changelog> {
+        Ok(Changelog {
+            filename: filename.to_string(),
+            source: read_source(reader)?,
+            modified: false
+        })
+    }
+
+    pub fn content(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Whether the changelog has a section for `version`.
+    pub fn has_version(&self, version: &Version) -> bool {
+        let version = version.to_string();
+        line_offsets(&self.source)
+            .any(|(_offset, line)| heading(line) == Some(version.as_str()))
+    }
+
+    /// The entries of the section for `version`, without its heading.
+    pub fn section(&self, version: &Version) -> Option<String> {
+        let version = version.to_string();
+        let section = line_offsets(&self.source)
+            .map(|(_offset, line)| line)
+            .skip_while(|line| heading(line) != Some(version.as_str()))
+            .skip(1)
+            .take_while(|line| heading(line).is_none() && !LINK_RE.is_match(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match section.trim() {
+            "" => None,
+            section => Some(section.to_string()),
+        }
+    }
+
+    pub fn check(&self, version: &Version) -> GradleResult<()> {
+        if !self.has_version(version) {
+            let reason = format!("no section for {} in '{}'", version, self.filename);
+            return Err(Error::ChangelogEntryMissing(reason))
+        }
+        Ok(())
+    }
+
+    /// Moves the `[Unreleased]` entries under a `## [version] - date`
+    /// heading, and updates the compare links at the bottom if there are
+    /// any. Does nothing when the version already has a section.
+    pub fn release(&mut self, version: &Version, date: &str) -> GradleResult<()> {
+        if self.has_version(version) {
+            return Ok(())
+        }
+        let missing = || {
+            let reason = format!(
+                "no entries under [Unreleased] in '{}' to release as {}", self.filename, version);
+            Error::ChangelogEntryMissing(reason)
+        };
+        let mut unreleased = None;
+        let mut in_unreleased = false;
+        let mut has_entries = false;
+        let mut link = None;
+        for (offset, line) in line_offsets(&self.source) {
+            if let Some(name) = heading(line) {
+                in_unreleased = name.eq_ignore_ascii_case("unreleased");
+                if in_unreleased {
+                    unreleased = Some(offset + line.len());
+                }
+            }
+            let entry = line.trim_start();
+            if in_unreleased && (entry.starts_with("- ") || entry.starts_with("* ")) {
+                has_entries = true;
+            }
+            if UNRELEASED_LINK_RE.is_match(line) {
+                link = Some((offset, line));
+            }
+        }
+        let unreleased = match unreleased {
+            Some(unreleased) if has_entries => unreleased,
+            _ => return Err(missing()),
+        };
+
+        let mut source = String::with_capacity(self.source.len() + 64);
+        source.push_str(&self.source[..unreleased]);
+        source.push_str(&format!("\n\n## [{}] - {}", version, date));
+        match link {
+            Some((offset, line)) => {
+                let caps = UNRELEASED_LINK_RE.captures(line).unwrap();
+                let (base, from, to) = (&caps["base"], &caps["from"], &caps["to"]);
+                let prefix = &from[..from.find(|c: char| c.is_ascii_digit()).unwrap_or(0)];
+                let tag = format!("{}{}", prefix, version);
+                source.push_str(&self.source[unreleased..offset]);
+                source.push_str(&format!("[Unreleased]: {}{}...{}\n", base, tag, to));
+                source.push_str(&format!("[{}]: {}{}...{}", version, base, from, tag));
+                source.push_str(&self.source[offset + line.len()..]);
+            },
+            None => source.push_str(&self.source[unreleased..]),
+        }
+        self.source = source;
+        self.modified = true;
+        Ok(())
+    }
+
+    pub fn write(&self) -> GradleResult<()> {
+        let mut fd = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.filename).map_err(|_err| {
+                let reason = format!("failed to open file for reading '{}'", self.filename);
+                Error::IoError(reason)
+            })?;
+        fd.write_all(self.source.as_bytes())
+            .map_err(|_err| {
+                Error::IoError("failed to write".to_string())
+            })
+    }
+}
+
+fn heading(line: &str) -> Option<&str> {
+    HEADING_RE.captures(line)
+        .map(|caps| caps.name("name").unwrap().as_str())
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    // Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
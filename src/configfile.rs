@@ -1,75 +1,443 @@
 use error::GradleResult;
 use error::Error;
 use std::io::{Read, Write};
-use std::fs::OpenOptions;
-use std::fs::File;
+use std::fs::{self, OpenOptions};
+use std::cell::Cell;
+use std::path::PathBuf;
 use std::env;
 use semver::Version;
-use version::GradleVersion;
+use version::{GradleVersion, SyncAction, SyncOptions, VersionCodeStrategy, VersionPolicy};
+use report::{SyncReport, VersionInfo};
 use version::sem_version_parse;
+use changelog::{Changelog, ChangelogMode, today};
+use fastlane::ReleaseNotes;
+use lock::{FileLock, DEFAULT_LOCK_TIMEOUT};
+use std::time::Duration;
+use state::VersionState;
 
-pub struct GradleFile<T> {
+pub struct GradleFile<T: ?Sized> {
     filename: String,
-    content: T
+    options: SyncOptions,
+    changelog: Option<(String, ChangelogMode)>,
+    release_notes: Option<ReleaseNotes>,
+    lock: Option<FileLock>,
+    state: Option<String>,
+    backup: bool,
+    backed_up: Cell<bool>,
+    content: Box<T>
 }
 
 impl<T> GradleFile<T> where T: ConfigurationFormat {
     pub fn new(filename: &str) -> GradleResult<GradleFile<T>> {
-        let fd = File::open(filename.clone())
-            .map_err(|_err| {
-                let reason = format!("failed to read file: {}", filename);
-                Error::IoError(reason)
-            })?;
-        Ok(Self {
+        Self::open(filename, DEFAULT_LOCK_TIMEOUT)
+    }
+
+    /// Opens `filename`, waiting up to `lock_timeout` for other syncs of the
+    /// same file to finish. The lock is held until the `GradleFile` is dropped.
+    pub fn open(filename: &str, lock_timeout: Duration) -> GradleResult<GradleFile<T>> {
+        let (lock, fd) = FileLock::open(filename, lock_timeout)?;
+        Ok(Self::from_reader(filename, fd)?.with_lock(lock))
+    }
+
+    /// Parses the content from `reader` instead of opening `filename`, which
+    /// only names the file in reports and is neither read nor locked.
+    pub fn from_reader<R: Read>(filename: &str, reader: R) -> GradleResult<GradleFile<T>> {
+        Ok(Self::with_content(filename, Box::new(T::from(reader)?)))
+    }
+
+    /// Erases the format, e.g. to mix formats in a `SyncSession`.
+    pub fn into_any(self) -> GradleFile<dyn ConfigurationFormat> where T: 'static {
+        GradleFile {
+            filename: self.filename,
+            options: self.options,
+            changelog: self.changelog,
+            release_notes: self.release_notes,
+            lock: self.lock,
+            state: self.state,
+            backup: self.backup,
+            backed_up: self.backed_up,
+            content: self.content,
+        }
+    }
+}
+
+impl<T> GradleFile<T> where T: ConfigurationFormat + ?Sized {
+    pub fn with_content(filename: &str, content: Box<T>) -> GradleFile<T> {
+        Self {
             filename: filename.to_string(),
-            content: T::from(fd)?
-        })
+            options: SyncOptions::default(),
+            changelog: None,
+            release_notes: None,
+            lock: None,
+            state: None,
+            backup: false,
+            backed_up: Cell::new(false),
+            content
+        }
+    }
+
+    /// Holds `lock` until the `GradleFile` is dropped.
+    pub fn with_lock(mut self, lock: FileLock) -> GradleFile<T> {
+        self.lock = Some(lock);
+        self
+    }
+
+    pub fn with_policy(mut self, policy: VersionPolicy) -> GradleFile<T> {
+        self.options.policy = policy;
+        self
+    }
+
+    pub fn set_policy(&mut self, policy: VersionPolicy) {
+        self.options.policy = policy;
+    }
+
+    pub fn policy(&self) -> VersionPolicy {
+        self.options.policy
+    }
+
+    pub fn with_code_strategy(mut self, code_strategy: VersionCodeStrategy) -> GradleFile<T> {
+        self.options.code_strategy = code_strategy;
+        self
+    }
+
+    pub fn with_options(mut self, options: SyncOptions) -> GradleFile<T> {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &SyncOptions {
+        &self.options
+    }
+
+    /// Also releases or checks the Keep a Changelog `filename` whenever a
+    /// sync changes the versionName, and requires its section in
+    /// `check_version`.
+    pub fn with_changelog(mut self, filename: &str, mode: ChangelogMode) -> GradleFile<T> {
+        self.changelog = Some((filename.to_string(), mode));
+        self
     }
 
-    pub fn sync_with_cargo(&mut self) -> GradleResult<()> {
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    pub fn current_version(&self) -> GradleResult<&GradleVersion> {
+        self.content.current_version()
+    }
+
+    pub fn version_info(&self) -> GradleResult<VersionInfo> {
+        let info = VersionInfo::new(&self.filename, self.content.current_version()?);
+        Ok(if self.content.has_version_code() { info } else { info.without_version_code() })
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.content.is_modified()
+    }
+
+    /// The file content with the current version.
+    pub fn render(&self) -> String {
+        self.content.render()
+    }
+
+    pub fn has_version_code(&self) -> bool {
+        self.content.has_version_code()
+    }
+
+    /// Raises the version in memory to `version_name` and `version_code`,
+    /// returning whether it changed.
+    pub(crate) fn reconcile(&mut self, version_name: &Version, version_code: u32) -> GradleResult<bool> {
+        let options = SyncOptions {
+            code_strategy: VersionCodeStrategy::Fixed(version_code),
+            ..SyncOptions::default()
+        };
+        self.content.sync_version_with(version_name, &options)
+            .map(|action| action.is_modified())
+    }
+
+    pub fn sync_with_cargo(&mut self) -> GradleResult<SyncReport> {
         let pkg_version = env::var("CARGO_PKG_VERSION").unwrap();
         let pkg_version = sem_version_parse(&pkg_version)?;
-        self.sync_version(&pkg_version)?;
+        self.sync_and_write(&pkg_version)
+    }
+
+    pub fn sync_and_write(&mut self, new_version: &Version) -> GradleResult<SyncReport> {
+        let prepared = self.prepare_sync(new_version)?;
+        self.commit_sync(prepared)
+    }
+
+    /// Syncs the content in memory and runs the checks of the changelog
+    /// step, without writing anything.
+    pub(crate) fn prepare_sync(&mut self, new_version: &Version) -> GradleResult<PreparedSync> {
+        let report = self.sync_version(new_version)?;
+        let changelog = self.changelog_step(&report, new_version)?;
+        Ok(PreparedSync { report, changelog })
+    }
+
+    /// Writes the file and runs the post-sync steps of a prepared sync.
+    pub(crate) fn commit_sync(&mut self, prepared: PreparedSync) -> GradleResult<SyncReport> {
+        let PreparedSync { mut report, changelog } = prepared;
         if self.content.is_modified() {
             self.write()?;
+            report.written = true;
+        }
+        if let Some(changelog) = changelog {
+            if changelog.is_modified() {
+                changelog.write()?;
+            }
+        }
+        if let Some(ref state) = self.state {
+            VersionState::record_synced(state, &self.filename, self.content.current_version()?)?;
+        }
+        if let Some(ref release_notes) = self.release_notes {
+            if report.old_version_code != report.new_version_code {
+                let version = self.content.current_version()?;
+                let notes = release_notes.update(version)?;
+                if !notes.missing_locales.is_empty() {
+                    report.warnings.push(format!(
+                        "release notes for versionCode {} missing in: {}",
+                        version.code(), notes.missing_locales.join(", ")));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Creates the fastlane release notes whenever a sync changes the
+    /// versionCode. Locales still without notes are reported as warnings.
+    pub fn with_release_notes(mut self, release_notes: ReleaseNotes) -> GradleFile<T> {
+        self.release_notes = Some(release_notes);
+        self
+    }
+
+    /// Records the versionCode of every synced versionName in the state
+    /// file `filename`, and reuses it whenever that versionName is synced
+    /// again, so repeated syncs never increase the versionCode twice.
+    pub fn with_state_file(mut self, filename: &str) -> GradleFile<T> {
+        self.state = Some(filename.to_string());
+        self
+    }
+
+    /// Copies the file to `<file>.bak` before `write` replaces it, so it can
+    /// be restored with `rollback`.
+    pub fn with_backup(mut self) -> GradleFile<T> {
+        self.backup = true;
+        self
+    }
+
+    pub fn backup_path(&self) -> PathBuf {
+        let mut path = self.filename.clone();
+        path.push_str(".bak");
+        PathBuf::from(path)
+    }
+
+    /// Restores the file from the backup made by the last `write`. The
+    /// content in memory keeps the synchronized version.
+    pub fn rollback(&self) -> GradleResult<()> {
+        if !self.backed_up.get() {
+            let reason = format!("no backup of '{}' to roll back to", self.filename);
+            return Err(Error::IoError(reason))
         }
+        fs::rename(self.backup_path(), &self.filename)
+            .map_err(|_err| {
+                let reason = format!("failed to restore '{}' from its backup", self.filename);
+                Error::IoError(reason)
+            })?;
+        self.backed_up.set(false);
         Ok(())
     }
 
-    pub fn sync_version(&mut self, new_version: &Version) -> GradleResult<()> {
-        self.content.sync_version(&new_version)
+    /// Runs the changelog step when the sync changed the versionName.
+    fn changelog_step(&self, report: &SyncReport, new_version: &Version) -> GradleResult<Option<Changelog>> {
+        let (filename, mode) = match self.changelog {
+            Some((ref filename, mode)) => (filename, mode),
+            None => return Ok(None),
+        };
+        if report.old_version_name == report.new_version_name {
+            return Ok(None)
+        }
+        let mut changelog = Changelog::open(filename)?;
+        match mode {
+            ChangelogMode::Release => changelog.release(new_version, &today())?,
+            ChangelogMode::Check => changelog.check(new_version)?,
+        }
+        Ok(Some(changelog))
+    }
+
+    pub fn sync_version(&mut self, new_version: &Version) -> GradleResult<SyncReport> {
+        let old_version = self.content.current_version()?.clone();
+        let old_lines = self.content.lines();
+        let mut options = self.options.clone();
+        if let Some(ref state) = self.state {
+            if let Some(code) = VersionState::open(state)?.version_code(&self.filename, new_version) {
+                if code < old_version.code() {
+                    let reason = format!(
+                        "versionCode {} recorded for {} in '{}' is lower than the current versionCode {} of '{}'",
+                        code, new_version, state, old_version.code(), self.filename);
+                    return Err(Error::InvalidVersionCode(reason))
+                }
+                options.code_strategy = VersionCodeStrategy::Fixed(code);
+            }
+        }
+        let action = self.content.sync_version_with(new_version, &options)?;
+        let mut report = SyncReport::new(
+            &self.filename, &old_version, self.content.current_version()?);
+        if !self.content.has_version_code() {
+            report = report.without_version_code();
+        }
+        report.diff_lines(&old_lines, &self.content.lines());
+        if let Some(warning) = action.warning() {
+            report.warnings.push(warning.to_string());
+        }
+        Ok(report)
+    }
+
+    pub fn check_with_cargo(&self) -> GradleResult<()> {
+        let pkg_version = env::var("CARGO_PKG_VERSION").unwrap();
+        let pkg_version = sem_version_parse(&pkg_version)?;
+        self.check_version(&pkg_version)
+    }
+
+    pub fn check_version(&self, expected_version: &Version) -> GradleResult<()> {
+        let current = self.content.current_version()?.version();
+        if current != expected_version {
+            let reason = format!(
+                "version out of sync in '{}' (file){} != (expected){}",
+                self.filename, current, expected_version
+            );
+            return Err(Error::VersionOutOfSync(reason))
+        }
+        if let Some((ref filename, _mode)) = self.changelog {
+            Changelog::open(filename)?.check(expected_version)?;
+        }
+        Ok(())
     }
 
     pub fn write(&self) -> GradleResult<()> {
+        if self.backup {
+            fs::copy(&self.filename, self.backup_path())
+                .map_err(|_err| {
+                    let reason = format!("failed to back up '{}'", self.filename);
+                    Error::IoError(reason)
+                })?;
+            self.backed_up.set(true);
+        }
         let mut fd = OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(&self.filename).map_err(|_err| {
                 let reason = format!("failed to open file for reading '{}'", self.filename);
                 Error::IoError(reason)
             })?;
-        self.content.write(&mut fd)
+        self.content.write_to(&mut fd)
     }
 }
 
-pub trait ConfigurationFormat where Self: Sized {
-    fn from<R: Read>(reader: R) -> GradleResult<Self>;
+/// A sync done in memory, waiting to be written by `commit_sync`.
+pub(crate) struct PreparedSync {
+    report: SyncReport,
+    changelog: Option<Changelog>,
+}
+
+/// Syncs and writes every file as one transaction: nothing is written
+/// unless every file syncs in memory, and when writing a file fails, the
+/// files already written are restored from their backups. Backups are
+/// only kept for files opened `with_backup`.
+///
+/// Only the files themselves are rolled back. Changelog releases, state
+/// file records and release notes of the files committed before the
+/// failure are kept.
+pub fn sync_all<T>(files: &mut [GradleFile<T>], new_version: &Version) -> GradleResult<Vec<SyncReport>>
+    where T: ConfigurationFormat + ?Sized {
+    let prepared = files.iter_mut()
+        .map(|file| file.prepare_sync(new_version))
+        .collect::<GradleResult<Vec<_>>>()?;
+    commit_all(files, prepared)
+}
+
+/// Commits the prepared syncs of `files`, rolling back every written file
+/// when one fails.
+pub(crate) fn commit_all<T>(files: &mut [GradleFile<T>], prepared: Vec<PreparedSync>) -> GradleResult<Vec<SyncReport>>
+    where T: ConfigurationFormat + ?Sized {
+    let keep_backups: Vec<bool> = files.iter().map(|file| file.backup).collect();
+    let mut reports = vec!();
+    let mut failure = None;
+    for (file, prepared) in files.iter_mut().zip(prepared) {
+        file.backup = true;
+        match file.commit_sync(prepared) {
+            Ok(report) => reports.push(report),
+            Err(error) => {
+                failure = Some(error);
+                break
+            },
+        }
+    }
+    let mut rollback_failures = vec!();
+    if failure.is_some() {
+        for file in files.iter().filter(|file| file.backed_up.get()) {
+            if let Err(error) = file.rollback() {
+                rollback_failures.push(format!("\n  {}: {}", file.filename, error));
+            }
+        }
+    }
+    for (file, keep_backup) in files.iter_mut().zip(keep_backups) {
+        file.backup = keep_backup;
+        if !keep_backup && file.backed_up.replace(false) {
+            let _ = fs::remove_file(file.backup_path());
+        }
+    }
+    match failure {
+        Some(error) if !rollback_failures.is_empty() => {
+            let reason = format!("{}, and failed to roll back:{}", error, rollback_failures.concat());
+            Err(Error::SyncFailed(reason))
+        },
+        Some(error) => Err(error),
+        None => Ok(reports),
+    }
+}
+
+/// A file format holding a versionName and versionCode.
+///
+/// The trait is object safe: only `from` and `write` require `Self: Sized`,
+/// so formats implemented outside this crate can be used as
+/// `Box<dyn ConfigurationFormat>`, e.g. through `GradleFile::with_content`
+/// or a `FormatProvider` registered in a `FormatRegistry`.
+pub trait ConfigurationFormat {
+    fn from<R: Read>(reader: R) -> GradleResult<Self> where Self: Sized;
     fn current_version(&self) -> GradleResult<&GradleVersion>;
     fn is_modified(&self) -> bool;
-    fn sync_version(&mut self, new_version: &Version) -> GradleResult<()>;
-    fn lines(&self) -> Vec<String>;
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction>;
+    /// The file content with the current version, identical to the
+    /// original content apart from the version tokens.
+    fn render(&self) -> String;
 
-    fn write<W: Write> (&self, writer: &mut W) -> GradleResult<()> {
-        for line in self.lines().iter() {
-            writer.write(line.as_bytes())
-                .map_err(|_err| {
-                    Error::IoError("failed to write".to_string())
-                })?;
-            writer.write(b"\n")
-                .map_err(|_err| {
-                    Error::IoError("failed to write".to_string())
-                })?;
-        };
-        Ok(())
+    /// Whether the format stores a versionCode. Formats without one hold a
+    /// versionCode of 1 that is never written nor reported.
+    fn has_version_code(&self) -> bool {
+        true
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.render().lines().map(|line| line.to_string()).collect()
+    }
+
+    fn sync_version(&mut self, new_version: &Version) -> GradleResult<()> {
+        self.sync_version_with(new_version, &SyncOptions::default())
+            .map(|_action| ())
+    }
+
+    fn sync_version_with_policy(&mut self, new_version: &Version, policy: VersionPolicy) -> GradleResult<SyncAction> {
+        self.sync_version_with(new_version, &SyncOptions::with_policy(policy))
+    }
+
+    fn write_to(&self, writer: &mut dyn Write) -> GradleResult<()> {
+        writer.write_all(self.render().as_bytes())
+            .map_err(|_err| {
+                Error::IoError("failed to write".to_string())
+            })
+    }
+
+    fn write<W: Write> (&self, writer: &mut W) -> GradleResult<()> where Self: Sized {
+        self.write_to(writer)
     }
 }
\ No newline at end of file
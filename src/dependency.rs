@@ -0,0 +1,175 @@
+use semver::Version;
+use configfile::{ConfigurationFormat, GradleFile};
+use error::GradleResult;
+use std::io::Read;
+use std::ops::Range;
+use error::Error;
+use lock::{FileLock, DEFAULT_LOCK_TIMEOUT};
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+use groovy::{tokenize, line_number, Token, TokenKind};
+
+/// The version of a `group:artifact` dependency in a Groovy or Kotlin
+/// build script.
+///
+/// Both string notation (`implementation "com.example:core:1.2.3"`) and map
+/// notation (`implementation group: 'com.example', name: 'core', version: '1.2.3'`,
+/// or with `=` in Kotlin) are found, and every occurrence is rewritten.
+pub struct DependencyContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl DependencyContent {
+    /// Reads the build script and finds the `coordinate`, given as
+    /// `group:artifact`.
+    pub fn parse<R: Read>(reader: R, coordinate: &str) -> GradleResult<Self> {
+        let source = read_source(reader)?;
+        let (group, artifact) = match coordinate.find(':') {
+            Some(index) => (&coordinate[..index], &coordinate[index + 1..]),
+            None => {
+                let reason = format!("invalid dependency '{}', expected group:artifact", coordinate);
+                return Err(Error::ParsingFailed(reason))
+            },
+        };
+        let tokens = tokenize(&source);
+        let mut version_name: Option<Version> = None;
+        let mut spans = VersionSpans::default();
+
+        let values = string_versions(&source, &tokens, group, artifact).into_iter()
+            .chain(map_versions(&source, &tokens, group, artifact));
+        for (token, range) in values {
+            if token.is_unterminated() {
+                let reason = format!("version of {} on line {} is an unterminated string",
+                                     coordinate, line_number(&source, token.start));
+                return Err(Error::ParsingFailed(reason))
+            }
+            if let TokenKind::String { interpolated: true, .. } = token.kind {
+                if source[range.clone()].contains('$') {
+                    let reason = format!(
+                        "version of {} on line {} is interpolated ({}), \
+                         replace it with a literal version such as \"1.0.0\" to synchronize it",
+                        coordinate, line_number(&source, token.start), &source[range]);
+                    return Err(Error::UnsupportedSyntax(reason))
+                }
+            }
+            if let Ok(parsed) = Version::parse(&source[range.clone()]) {
+                version_name = version_name.or(Some(parsed));
+                spans.name.push(range);
+            }
+        }
+        spans.name.sort_by_key(|range| range.start);
+        match version_name {
+            Some(version_name) => Ok(Self{
+                source,
+                spans,
+                version: GradleVersion::new(1, version_name),
+                modified: false
+            }),
+            None => Err(Error::VersionNotFound(format!("failed to find dependency {}", coordinate))),
+        }
+    }
+}
+
+impl GradleFile<DependencyContent> {
+    /// Opens a build script to synchronize the `group:artifact` dependency.
+    pub fn with_dependency(filename: &str, coordinate: &str) -> GradleResult<GradleFile<DependencyContent>> {
+        let (lock, fd) = FileLock::open(filename, DEFAULT_LOCK_TIMEOUT)?;
+        Ok(Self::with_content(filename, Box::new(DependencyContent::parse(fd, coordinate)?)).with_lock(lock))
+    }
+}
+
+impl ConfigurationFormat for DependencyContent {
+    /// Always fails, as the dependency to look for has to be given with
+    /// `DependencyContent::parse`.
+    fn from<R: Read>(_reader: R) -> GradleResult<Self>{
+        let reason = "a dependency needs its group:artifact, use DependencyContent::parse".to_string();
+        Err(Error::UnsupportedFormat(reason))
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn has_version_code(&self) -> bool {
+        false
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
+
+/// Finds the version part of `"group:artifact:version"` strings, with an
+/// optional `:classifier` or `@extension` after the version.
+fn string_versions<'a>(source: &str, tokens: &'a [Token], group: &str, artifact: &str) -> Vec<(&'a Token, Range<usize>)> {
+    let prefix = format!("{}:{}:", group, artifact);
+    tokens.iter()
+        .filter(|token| matches!(token.kind, TokenKind::String { .. }))
+        .filter_map(|token| {
+            let content = token.content();
+            let text = &source[content.clone()];
+            if !text.starts_with(&prefix) {
+                return None
+            }
+            let start = content.start + prefix.len();
+            let end = source[start..content.end].find([':', '@'])
+                .map(|index| start + index)
+                .unwrap_or(content.end);
+            Some((token, start..end))
+        })
+        .collect()
+}
+
+/// Finds the `version` of `group: 'g', name: 'a', version: 'v'` arguments,
+/// written with `:` in Groovy or `=` in Kotlin and in any order.
+fn map_versions<'a>(source: &'a str, tokens: &'a [Token], group: &str, artifact: &str) -> Vec<(&'a Token, Range<usize>)> {
+    let mut versions = vec!();
+    let mut index = 0;
+    while index < tokens.len() {
+        let (arguments, next) = named_arguments(source, tokens, index);
+        let value = |key: &str| arguments.iter()
+            .find(|argument| argument.0 == key)
+            .map(|argument| argument.1);
+        let matches = value("group").map(|token| &source[token.content()]) == Some(group)
+            && value("name").map(|token| &source[token.content()]) == Some(artifact);
+        if let (true, Some(version)) = (matches, value("version")) {
+            versions.push((version, version.content()));
+        }
+        index = next.max(index + 1);
+    }
+    versions
+}
+
+/// Collects the `key: 'value'` arguments starting at `index`, returning them
+/// with the index after the last one.
+fn named_arguments<'a>(source: &'a str, tokens: &'a [Token], mut index: usize) -> (Vec<(&'a str, &'a Token)>, usize) {
+    let mut arguments = vec!();
+    loop {
+        let argument = match (tokens.get(index), tokens.get(index + 1), tokens.get(index + 2)) {
+            (Some(key), Some(separator), Some(value))
+                if key.kind == TokenKind::Identifier
+                    && matches!(separator.kind, TokenKind::Symbol(':') | TokenKind::Symbol('='))
+                    && matches!(value.kind, TokenKind::String { .. }) => (key.text(source), value),
+            _ => return (arguments, index),
+        };
+        arguments.push(argument);
+        index += 3;
+        if tokens.get(index).map(|token| token.kind) != Some(TokenKind::Symbol(',')) {
+            return (arguments, index)
+        }
+        index += 1;
+        while tokens.get(index).map(|token| token.kind) == Some(TokenKind::Newline) {
+            index += 1;
+        }
+    }
+}
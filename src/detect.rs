@@ -0,0 +1,274 @@
+use std::io::Read;
+use std::path::Path;
+use semver::Version;
+use configfile::{ConfigurationFormat, GradleFile};
+use error::{Error, GradleResult};
+use lock::{FileLock, DEFAULT_LOCK_TIMEOUT};
+use report::SyncReport;
+use span::read_source;
+use buildgradle::BuildGradleContent;
+use buildgradlekts::BuildGradleKtsContent;
+use properties::PropertiesContent;
+use versioncatalog::VersionCatalogContent;
+use manifest::AndroidManifestContent;
+use plist::InfoPlistContent;
+use pbxproj::XcodeProjectContent;
+use pubspec::PubspecContent;
+use packagejson::PackageJsonContent;
+use gradleproject::GradleProjectContent;
+use pom::PomContent;
+
+/// The configuration formats that can be selected at runtime.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FormatKind {
+    BuildGradle,
+    BuildGradleKts,
+    Properties,
+    VersionCatalog,
+    AndroidManifest,
+    InfoPlist,
+    XcodeProject,
+    Pubspec,
+    PackageJson,
+    GradleProject,
+    Pom,
+}
+
+impl FormatKind {
+    pub fn all() -> &'static [FormatKind] {
+        &[
+            FormatKind::BuildGradle,
+            FormatKind::BuildGradleKts,
+            FormatKind::Properties,
+            FormatKind::VersionCatalog,
+            FormatKind::AndroidManifest,
+            FormatKind::InfoPlist,
+            FormatKind::XcodeProject,
+            FormatKind::Pubspec,
+            FormatKind::PackageJson,
+            FormatKind::GradleProject,
+            FormatKind::Pom,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            FormatKind::BuildGradle => "build.gradle",
+            FormatKind::BuildGradleKts => "build.gradle.kts",
+            FormatKind::Properties => "gradle.properties",
+            FormatKind::VersionCatalog => "libs.versions.toml",
+            FormatKind::AndroidManifest => "AndroidManifest.xml",
+            FormatKind::InfoPlist => "Info.plist",
+            FormatKind::XcodeProject => "project.pbxproj",
+            FormatKind::Pubspec => "pubspec.yaml",
+            FormatKind::PackageJson => "package.json",
+            FormatKind::GradleProject => "build.gradle project version",
+            FormatKind::Pom => "pom.xml",
+        }
+    }
+
+    /// Selects the format from the file name or extension only.
+    pub fn from_filename(filename: &str) -> Option<FormatKind> {
+        let name = Path::new(filename)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(filename);
+        if name.ends_with(".gradle.kts") {
+            Some(FormatKind::BuildGradleKts)
+        } else if name.ends_with(".gradle") {
+            Some(FormatKind::BuildGradle)
+        } else if name.ends_with(".properties") {
+            Some(FormatKind::Properties)
+        } else if name.ends_with(".versions.toml") {
+            Some(FormatKind::VersionCatalog)
+        } else if name == "AndroidManifest.xml" {
+            Some(FormatKind::AndroidManifest)
+        } else if name.ends_with(".plist") {
+            Some(FormatKind::InfoPlist)
+        } else if name.ends_with(".pbxproj") {
+            Some(FormatKind::XcodeProject)
+        } else if name == "pubspec.yaml" {
+            Some(FormatKind::Pubspec)
+        } else if name == "package.json" || name == "package-lock.json" {
+            Some(FormatKind::PackageJson)
+        } else if name == "pom.xml" || name.ends_with(".pom") {
+            Some(FormatKind::Pom)
+        } else {
+            None
+        }
+    }
+
+    /// Selects the format from the file name, telling Android build scripts
+    /// apart from ones that only set the project version.
+    pub fn from_file(filename: &str, content: &str) -> Option<FormatKind> {
+        match FormatKind::from_filename(filename) {
+            Some(FormatKind::BuildGradle) | Some(FormatKind::BuildGradleKts)
+                if content.contains("version") && !content.contains("versionCode") => Some(FormatKind::GradleProject),
+            kind => kind,
+        }
+    }
+
+    /// Guesses the format by looking for the version definitions.
+    pub fn from_content(content: &str) -> Option<FormatKind> {
+        if content.contains("android:versionCode") {
+            Some(FormatKind::AndroidManifest)
+        } else if content.contains("<modelVersion>") {
+            Some(FormatKind::Pom)
+        } else if content.contains("CFBundleShortVersionString") {
+            Some(FormatKind::InfoPlist)
+        } else if content.contains("MARKETING_VERSION") {
+            Some(FormatKind::XcodeProject)
+        } else if content.contains("[versions]") {
+            Some(FormatKind::VersionCatalog)
+        } else if content.contains("projectVersion") {
+            Some(FormatKind::Properties)
+        } else if content.contains("versionCode") {
+            if content.contains("versionName =") || content.contains("versionCode =") {
+                Some(FormatKind::BuildGradleKts)
+            } else {
+                Some(FormatKind::BuildGradle)
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn detect(filename: &str, content: &str) -> GradleResult<FormatKind> {
+        FormatKind::from_file(filename, content)
+            .or_else(|| FormatKind::from_content(content))
+            .ok_or_else(|| {
+                let reason = format!("unable to detect the format of '{}'", filename);
+                Error::UnsupportedFormat(reason)
+            })
+    }
+}
+
+/// Builds a configuration format at runtime.
+///
+/// Implement this trait to plug a format that lives outside this crate
+/// (e.g. a custom `version.json`) into a `FormatRegistry`.
+pub trait FormatProvider {
+    fn name(&self) -> &str;
+    fn matches_filename(&self, filename: &str) -> bool;
+    /// Whether the file name selects this format, where the content may
+    /// help to choose between formats sharing a file name.
+    fn matches_file(&self, filename: &str, _content: &str) -> bool {
+        self.matches_filename(filename)
+    }
+    fn matches_content(&self, _content: &str) -> bool {
+        false
+    }
+    fn parse(&self, reader: &mut dyn Read) -> GradleResult<Box<dyn ConfigurationFormat>>;
+}
+
+impl FormatProvider for FormatKind {
+    fn name(&self) -> &str {
+        FormatKind::name(self)
+    }
+
+    fn matches_filename(&self, filename: &str) -> bool {
+        FormatKind::from_filename(filename) == Some(*self)
+    }
+
+    fn matches_file(&self, filename: &str, content: &str) -> bool {
+        FormatKind::from_file(filename, content) == Some(*self)
+    }
+
+    fn matches_content(&self, content: &str) -> bool {
+        FormatKind::from_content(content) == Some(*self)
+    }
+
+    fn parse(&self, reader: &mut dyn Read) -> GradleResult<Box<dyn ConfigurationFormat>> {
+        Ok(match *self {
+            FormatKind::BuildGradle => Box::new(<BuildGradleContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::BuildGradleKts => Box::new(<BuildGradleKtsContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::Properties => Box::new(<PropertiesContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::VersionCatalog => Box::new(<VersionCatalogContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::AndroidManifest => Box::new(<AndroidManifestContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::InfoPlist => Box::new(<InfoPlistContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::XcodeProject => Box::new(<XcodeProjectContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::Pubspec => Box::new(<PubspecContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::PackageJson => Box::new(<PackageJsonContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::GradleProject => Box::new(<GradleProjectContent as ConfigurationFormat>::from(reader)?),
+            FormatKind::Pom => Box::new(<PomContent as ConfigurationFormat>::from(reader)?),
+        })
+    }
+}
+
+/// Chooses a `FormatProvider` for a file.
+///
+/// Providers registered with `register` take precedence over the built-in
+/// formats. A provider matching the file name wins over one that only
+/// recognizes the content.
+pub struct FormatRegistry {
+    providers: Vec<Box<dyn FormatProvider>>
+}
+
+impl Default for FormatRegistry {
+    fn default() -> FormatRegistry {
+        FormatRegistry {
+            providers: FormatKind::all().iter()
+                .map(|kind| Box::new(*kind) as Box<dyn FormatProvider>)
+                .collect()
+        }
+    }
+}
+
+impl FormatRegistry {
+    pub fn new() -> FormatRegistry {
+        FormatRegistry::default()
+    }
+
+    pub fn register(&mut self, provider: Box<dyn FormatProvider>) {
+        self.providers.insert(0, provider);
+    }
+
+    pub fn detect(&self, filename: &str, content: &str) -> GradleResult<&dyn FormatProvider> {
+        self.providers.iter()
+            .find(|provider| provider.matches_file(filename, content))
+            .or_else(|| self.providers.iter().find(|provider| provider.matches_content(content)))
+            .map(|provider| provider.as_ref())
+            .ok_or_else(|| {
+                let reason = format!("unable to detect the format of '{}'", filename);
+                Error::UnsupportedFormat(reason)
+            })
+    }
+
+    pub fn open(&self, filename: &str) -> GradleResult<AnyGradleFile> {
+        let (lock, fd) = FileLock::open(filename, DEFAULT_LOCK_TIMEOUT)?;
+        Ok(self.read(filename, fd)?.with_lock(lock))
+    }
+
+    /// Parses the content from `reader` with the format detected for
+    /// `filename`, without touching the file system.
+    pub fn read<R: Read>(&self, filename: &str, reader: R) -> GradleResult<AnyGradleFile> {
+        let content = read_source(reader)?;
+        let provider = self.detect(filename, &content)?;
+        let content = provider.parse(&mut content.as_bytes())?;
+        Ok(GradleFile::with_content(filename, content))
+    }
+}
+
+/// A configuration file whose format is only known at runtime.
+pub type AnyGradleFile = GradleFile<dyn ConfigurationFormat>;
+
+impl GradleFile<dyn ConfigurationFormat> {
+    /// Opens `filename` with the format detected by the default registry.
+    pub fn new(filename: &str) -> GradleResult<AnyGradleFile> {
+        FormatRegistry::default().open(filename)
+    }
+
+    /// Parses the content from `reader` with the format detected for
+    /// `filename` by the default registry. Nothing is read from disk.
+    pub fn from_reader<R: Read>(filename: &str, reader: R) -> GradleResult<AnyGradleFile> {
+        FormatRegistry::default().read(filename, reader)
+    }
+}
+
+/// Syncs `content` in `format` to `new_version` entirely in memory,
+/// returning the new content and the report of the sync.
+pub fn sync_str(format: &dyn FormatProvider, content: &str, new_version: &Version) -> GradleResult<(String, SyncReport)> {
+    let mut file = AnyGradleFile::with_content(format.name(), format.parse(&mut content.as_bytes())?);
+    let report = file.sync_version(new_version)?;
+    Ok((file.render(), report))
+}
@@ -1,10 +1,20 @@
 use std::io::Error as IoError;
+use std::error::Error as StdError;
+use std::fmt;
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Error {
     ParsingFailed(String),
     VersionNotFound(String),
     VersionNotIncreasing(String),
+    VersionOutOfSync(String),
+    VersionConflict(String),
+    InvalidVersionCode(String),
+    UnsupportedFormat(String),
+    UnsupportedSyntax(String),
+    ChangelogEntryMissing(String),
+    LockTimeout(String),
+    SyncFailed(String),
     IoError(String),
 }
 
@@ -16,3 +26,24 @@ impl From<IoError> for Error {
         Error::IoError(reason)
     }
 }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::ParsingFailed(ref reason) |
+            Error::VersionNotFound(ref reason) |
+            Error::VersionNotIncreasing(ref reason) |
+            Error::VersionOutOfSync(ref reason) |
+            Error::VersionConflict(ref reason) |
+            Error::InvalidVersionCode(ref reason) |
+            Error::UnsupportedFormat(ref reason) |
+            Error::UnsupportedSyntax(ref reason) |
+            Error::ChangelogEntryMissing(ref reason) |
+            Error::LockTimeout(ref reason) |
+            Error::SyncFailed(ref reason) |
+            Error::IoError(ref reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl StdError for Error {}
@@ -0,0 +1,121 @@
+//! Play Store release notes for fastlane `supply`, kept in
+//! `fastlane/metadata/android/<locale>/changelogs/<versionCode>.txt`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use error::{Error, GradleResult};
+use changelog::Changelog;
+use version::GradleVersion;
+
+/// Where the text of new release notes comes from.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ReleaseNotesSource {
+    /// A text in which `{versionName}` and `{versionCode}` are replaced.
+    Template(String),
+    /// The section of the version in a Keep a Changelog file.
+    Changelog(String),
+}
+
+/// Creates the release notes of a new versionCode in the locales it was
+/// given, and reports the other locales that do not have notes yet.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ReleaseNotes {
+    metadata_dir: PathBuf,
+    locales: Vec<String>,
+    source: Option<ReleaseNotesSource>,
+}
+
+/// The outcome of `ReleaseNotes::update`.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ReleaseNotesReport {
+    pub created: Vec<PathBuf>,
+    pub missing_locales: Vec<String>,
+}
+
+impl ReleaseNotes {
+    /// `metadata_dir` is the directory holding the locales, usually
+    /// `fastlane/metadata/android`.
+    pub fn new<P: AsRef<Path>>(metadata_dir: P) -> ReleaseNotes {
+        ReleaseNotes {
+            metadata_dir: metadata_dir.as_ref().to_path_buf(),
+            locales: vec!(),
+            source: None,
+        }
+    }
+
+    /// Adds a locale, e.g. `en-US`, to write the release notes for.
+    pub fn locale(mut self, locale: &str) -> ReleaseNotes {
+        self.locales.push(locale.to_string());
+        self
+    }
+
+    pub fn with_template(mut self, template: &str) -> ReleaseNotes {
+        self.source = Some(ReleaseNotesSource::Template(template.to_string()));
+        self
+    }
+
+    pub fn with_changelog(mut self, filename: &str) -> ReleaseNotes {
+        self.source = Some(ReleaseNotesSource::Changelog(filename.to_string()));
+        self
+    }
+
+    fn notes(&self, version: &GradleVersion) -> GradleResult<Option<String>> {
+        match self.source {
+            Some(ReleaseNotesSource::Template(ref template)) => Ok(Some(template
+                .replace("{versionName}", &version.version().to_string())
+                .replace("{versionCode}", &version.code().to_string()))),
+            Some(ReleaseNotesSource::Changelog(ref filename)) => {
+                Ok(Changelog::open(filename)?.section(version.version()))
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Writes `<versionCode>.txt` for the configured locales, keeping any
+    /// existing file, and lists every locale still without one.
+    pub fn update(&self, version: &GradleVersion) -> GradleResult<ReleaseNotesReport> {
+        let mut report = ReleaseNotesReport::default();
+        let filename = format!("{}.txt", version.code());
+        if let Some(notes) = self.notes(version)? {
+            for locale in &self.locales {
+                let changelogs = self.metadata_dir.join(locale).join("changelogs");
+                let path = changelogs.join(&filename);
+                if path.exists() {
+                    continue
+                }
+                fs::create_dir_all(&changelogs)
+                    .and_then(|_| fs::write(&path, format!("{}\n", notes.trim_end())))
+                    .map_err(|_err| {
+                        let reason = format!("failed to write release notes '{}'", path.display());
+                        Error::IoError(reason)
+                    })?;
+                report.created.push(path);
+            }
+        }
+        report.missing_locales = self.locales_without(&filename)?;
+        Ok(report)
+    }
+
+    /// The configured locales and locale directories without
+    /// `changelogs/<filename>`, sorted.
+    fn locales_without(&self, filename: &str) -> GradleResult<Vec<String>> {
+        let mut locales = self.locales.clone();
+        match fs::read_dir(&self.metadata_dir) {
+            Ok(entries) => locales.extend(entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))),
+            Err(_) if !self.metadata_dir.exists() => (),
+            Err(_) => {
+                let reason = format!("failed to read directory '{}'", self.metadata_dir.display());
+                return Err(Error::IoError(reason))
+            },
+        };
+        let mut missing: Vec<String> = locales.into_iter()
+            .filter(|locale| !self.metadata_dir.join(locale).join("changelogs").join(filename).exists())
+            .collect();
+        missing.sort();
+        missing.dedup();
+        Ok(missing)
+    }
+}
@@ -0,0 +1,85 @@
+use semver::Version;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+use groovy::{tokenize, find_statements, line_number, TokenKind};
+
+/// The project-level `version = '1.2.3'` of a Groovy or Kotlin build script,
+/// as used when publishing to Maven.
+///
+/// Only statements at the top of the script or in an `allprojects` block
+/// count, so `version` inside dependency constraints is left alone.
+pub struct GradleProjectContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for GradleProjectContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let tokens = tokenize(&source);
+        let statements: Vec<_> = find_statements(&source, &tokens, "version").into_iter()
+            .filter(|statement| statement.blocks.is_empty() || statement.in_block("allprojects"))
+            .collect();
+        let mut version_name: Option<Version> = None;
+        let mut spans = VersionSpans::default();
+
+        for statement in &statements {
+            if statement.value.is_unterminated() {
+                let reason = format!("project version on line {} is an unterminated string",
+                                     line_number(&source, statement.value.start));
+                return Err(Error::ParsingFailed(reason))
+            }
+            match statement.value.kind {
+                TokenKind::String { interpolated: false, .. } => {},
+                TokenKind::String { interpolated: true, .. } => {
+                    let reason = format!(
+                        "project version on line {} is interpolated ({}), \
+                         replace it with a literal version such as \"1.0.0\" to synchronize it",
+                        line_number(&source, statement.value.start),
+                        statement.value.text(&source)
+                    );
+                    return Err(Error::UnsupportedSyntax(reason))
+                },
+                _ => continue,
+            }
+            if let Ok(parsed) = Version::parse(&source[statement.value.content()]) {
+                version_name = version_name.or(Some(parsed));
+                spans.name.push(statement.value.content());
+            }
+        }
+        match version_name {
+            Some(version_name) => Ok(Self{
+                source,
+                spans,
+                version: GradleVersion::new(1, version_name),
+                modified: false
+            }),
+            None => Err(Error::VersionNotFound("failed to find project version".to_string())),
+        }
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn has_version_code(&self) -> bool {
+        false
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
@@ -0,0 +1,285 @@
+//! A lightweight Groovy tokenizer.
+//!
+//! It only knows enough about the language to skip comments and string
+//! literals, so that `versionCode`/`versionName` statements can be told
+//! apart from text that merely looks like one.
+
+use std::ops::Range;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Quote {
+    Single,
+    Double,
+    TripleSingle,
+    TripleDouble,
+}
+
+impl Quote {
+    fn len(&self) -> usize {
+        match *self {
+            Quote::Single | Quote::Double => 1,
+            Quote::TripleSingle | Quote::TripleDouble => 3,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TokenKind {
+    Identifier,
+    Number,
+    String { quote: Quote, interpolated: bool, terminated: bool },
+    Symbol(char),
+    Newline,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+
+    /// The span of the token without its quotes.
+    pub fn content(&self) -> Range<usize> {
+        match self.kind {
+            TokenKind::String { quote, terminated: true, .. } => {
+                (self.start + quote.len())..(self.end - quote.len())
+            },
+            TokenKind::String { quote, terminated: false, .. } => (self.start + quote.len())..self.end,
+            _ => self.start..self.end,
+        }
+    }
+
+    /// Whether the token is a string literal cut off by the end of the line
+    /// or file before its closing quote.
+    pub fn is_unterminated(&self) -> bool {
+        matches!(self.kind, TokenKind::String { terminated: false, .. })
+    }
+}
+
+/// A `name value`, `name = value` or `name(value)` statement found by
+/// `find_statements`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Statement {
+    pub value: Token,
+    pub blocks: Vec<String>,
+}
+
+impl Statement {
+    pub fn in_block(&self, block: &str) -> bool {
+        self.blocks.iter().any(|name| name == block)
+    }
+}
+
+/// A `key: value` entry of a map literal found by `find_map_entries`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MapEntry {
+    pub key: String,
+    pub value: Token,
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let bytes = source.as_bytes();
+    let mut tokens = vec!();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let c = bytes[pos];
+        if c == b'\n' {
+            pos += 1;
+            tokens.push(Token { kind: TokenKind::Newline, start, end: pos });
+        } else if c.is_ascii_whitespace() {
+            pos += 1;
+        } else if source[pos..].starts_with("//") {
+            pos = source[pos..].find('\n').map(|i| pos + i).unwrap_or(bytes.len());
+        } else if source[pos..].starts_with("/*") {
+            pos = source[pos + 2..].find("*/").map(|i| pos + i + 4).unwrap_or(bytes.len());
+        } else if c == b'\'' || c == b'"' {
+            let quote = match (c, source[pos..].starts_with("'''"), source[pos..].starts_with("\"\"\"")) {
+                (_, true, _) => Quote::TripleSingle,
+                (_, _, true) => Quote::TripleDouble,
+                (b'\'', _, _) => Quote::Single,
+                _ => Quote::Double,
+            };
+            let (end, interpolated, terminated) = scan_string(source, pos, quote);
+            pos = end;
+            tokens.push(Token { kind: TokenKind::String { quote, interpolated, terminated }, start, end });
+        } else if c.is_ascii_digit() {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Number, start, end: pos });
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'$' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'$') {
+                pos += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Identifier, start, end: pos });
+        } else {
+            let symbol = source[pos..].chars().next().unwrap();
+            pos += symbol.len_utf8();
+            tokens.push(Token { kind: TokenKind::Symbol(symbol), start, end: pos });
+        }
+    }
+    tokens
+}
+
+/// Returns the end of the string literal starting at `start`, whether it
+/// contains a `$` interpolation and whether its closing quote was found.
+fn scan_string(source: &str, start: usize, quote: Quote) -> (usize, bool, bool) {
+    let delimiter = &source[start..start + quote.len()];
+    let double = quote == Quote::Double || quote == Quote::TripleDouble;
+    let bytes = source.as_bytes();
+    let mut interpolated = false;
+    let mut pos = start + quote.len();
+    while pos < bytes.len() {
+        if bytes[pos] == b'\\' {
+            pos += 1;
+            pos += source[pos..].chars().next().map_or(0, char::len_utf8);
+        } else if source[pos..].starts_with(delimiter) {
+            return (pos + delimiter.len(), interpolated, true)
+        } else if bytes[pos] == b'\n' && quote.len() == 1 {
+            return (pos, interpolated, false)
+        } else {
+            interpolated |= double && bytes[pos] == b'$';
+            pos += source[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    (bytes.len(), interpolated, false)
+}
+
+/// Finds the statements assigning `name`, skipping anything inside comments or
+/// strings and any `name` that is not at the start of a statement.
+pub fn find_statements(source: &str, tokens: &[Token], name: &str) -> Vec<Statement> {
+    statement_names(source, tokens, name).into_iter()
+        .filter_map(|(index, blocks)| {
+            statement_value(tokens, index + 1)
+                .map(|value| Statement { value: value.clone(), blocks })
+        })
+        .collect()
+}
+
+/// Finds the entries of a `name = [key: value, ...]` map literal.
+pub fn find_map_entries(source: &str, tokens: &[Token], name: &str) -> Vec<MapEntry> {
+    statement_names(source, tokens, name).into_iter()
+        .filter_map(|(index, _blocks)| map_entries(source, tokens, index + 1))
+        .flat_map(|entries| entries.into_iter())
+        .collect()
+}
+
+/// Returns the index and enclosing blocks of every `name` token that starts
+/// a statement. Qualifiers such as `ext.name` or `project.ext.name` are
+/// part of the statement.
+fn statement_names(source: &str, tokens: &[Token], name: &str) -> Vec<(usize, Vec<String>)> {
+    let mut names = vec!();
+    let mut blocks: Vec<String> = vec!();
+    let mut statement_start = true;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Symbol('{') => {
+                let block = match index.checked_sub(1).map(|i| &tokens[i]) {
+                    Some(previous) if previous.kind == TokenKind::Identifier => previous.text(source),
+                    _ => "",
+                };
+                blocks.push(block.to_string());
+            },
+            TokenKind::Symbol('}') => {
+                blocks.pop();
+            },
+            TokenKind::Identifier if statement_start && token.text(source) == name => {
+                names.push((index, blocks.clone()));
+            },
+            _ => {},
+        }
+        let qualifier = statement_start && match token.kind {
+            TokenKind::Identifier => {
+                (token.text(source) == "ext" || token.text(source) == "project")
+                    && tokens.get(index + 1).map(|next| next.kind) == Some(TokenKind::Symbol('.'))
+            },
+            TokenKind::Symbol('.') => true,
+            _ => false,
+        };
+        statement_start = qualifier || matches!(token.kind,
+            TokenKind::Newline | TokenKind::Symbol('{') | TokenKind::Symbol('}') | TokenKind::Symbol(';'));
+    }
+    names
+}
+
+fn map_entries(source: &str, tokens: &[Token], mut index: usize) -> Option<Vec<MapEntry>> {
+    let significant = |index: &mut usize| -> Option<&Token> {
+        while tokens.get(*index)?.kind == TokenKind::Newline {
+            *index += 1;
+        }
+        let token = tokens.get(*index);
+        *index += 1;
+        token
+    };
+    let mut token = significant(&mut index)?;
+    if token.kind == TokenKind::Symbol('=') {
+        token = significant(&mut index)?;
+    }
+    if token.kind != TokenKind::Symbol('[') {
+        return None
+    }
+    let mut entries = vec!();
+    loop {
+        let key = significant(&mut index)?;
+        let key = match key.kind {
+            TokenKind::Symbol(']') => return Some(entries),
+            TokenKind::String { interpolated: false, .. } => source[key.content()].to_string(),
+            TokenKind::Identifier => key.text(source).to_string(),
+            _ => return None,
+        };
+        if significant(&mut index)?.kind != TokenKind::Symbol(':') {
+            return None
+        }
+        let value = significant(&mut index)?;
+        if value.kind != TokenKind::Number {
+            return None
+        }
+        entries.push(MapEntry { key, value: value.clone() });
+        match significant(&mut index)?.kind {
+            TokenKind::Symbol(',') => {},
+            TokenKind::Symbol(']') => return Some(entries),
+            _ => return None,
+        }
+    }
+}
+
+/// Matches the value of `name value`, `name = value` and `name(value)`.
+fn statement_value(tokens: &[Token], index: usize) -> Option<&Token> {
+    let (value_index, end_index) = match tokens.get(index)?.kind {
+        TokenKind::Symbol('=') => (index + 1, index + 2),
+        TokenKind::Symbol('(') => match tokens.get(index + 2)?.kind {
+            TokenKind::Symbol(')') => (index + 1, index + 3),
+            _ => return None,
+        },
+        _ => (index, index + 1),
+    };
+    let value = tokens.get(value_index)?;
+    match value.kind {
+        TokenKind::Number | TokenKind::String { .. } => {},
+        _ => return None,
+    }
+    match tokens.get(end_index).map(|token| token.kind) {
+        None | Some(TokenKind::Newline) | Some(TokenKind::Symbol(';')) | Some(TokenKind::Symbol('}')) => Some(value),
+        _ => None,
+    }
+}
+
+/// Picks the statement that defines the value: the first one inside a
+/// `defaultConfig` block, or else the first one in the file.
+pub fn primary_statement(statements: &[Statement]) -> Option<&Statement> {
+    statements.iter()
+        .find(|statement| statement.in_block("defaultConfig"))
+        .or_else(|| statements.first())
+}
+
+/// Returns the line number, counted from 1, of a byte offset.
+pub fn line_number(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
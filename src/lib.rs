@@ -1,20 +1,95 @@
 extern crate semver;
 extern crate regex;
+#[macro_use]
+extern crate lazy_static;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(test)]
+#[macro_use]
+extern crate proptest;
 
 #[cfg(test)]
 mod tests;
 
 mod version;
+pub use version::GradleVersion;
+pub use version::sem_version_parse;
+pub use version::VersionPolicy;
+pub use version::SyncAction;
+pub use version::SyncOptions;
+pub use version::{VersionCodeStrategy, SemverCode, AbiSplits, MAX_VERSION_CODE, validate_version_code};
+mod report;
+pub use report::SyncReport;
+pub use report::ChangedLine;
+pub use report::VersionInfo;
 mod configfile;
 pub use configfile::ConfigurationFormat;
 pub use configfile::GradleFile;
+pub use configfile::sync_all;
+mod session;
+pub use session::{SyncSession, ConsistencyPolicy};
+mod span;
+mod groovy;
 mod buildgradle;
 pub use buildgradle::BuildGradleContent;
+pub use buildgradle::{parse_version_code_line, parse_version_name_line};
+pub use buildgradle::{replace_version_code, replace_version_name};
+mod buildgradlekts;
+pub use buildgradlekts::BuildGradleKtsContent;
 mod properties;
 pub use properties::PropertiesContent;
+pub use properties::{parse_project_version_from_line, replace_project_version_from_line};
+mod versioncatalog;
+pub use versioncatalog::VersionCatalogContent;
+mod manifest;
+pub use manifest::AndroidManifestContent;
+mod plist;
+pub use plist::InfoPlistContent;
+mod pbxproj;
+pub use pbxproj::XcodeProjectContent;
+mod pubspec;
+pub use pubspec::PubspecContent;
+mod packagejson;
+pub use packagejson::PackageJsonContent;
+mod gradleproject;
+pub use gradleproject::GradleProjectContent;
+mod pom;
+pub use pom::PomContent;
+mod dependency;
+pub use dependency::DependencyContent;
+mod changelog;
+pub use changelog::{Changelog, ChangelogMode};
+mod fastlane;
+pub use fastlane::{ReleaseNotes, ReleaseNotesSource, ReleaseNotesReport};
+mod lock;
+pub use lock::{FileLock, DEFAULT_LOCK_TIMEOUT};
+mod state;
+pub use state::VersionState;
+mod watch;
+pub use watch::Watcher;
+mod detect;
+pub use detect::FormatKind;
+pub use detect::AnyGradleFile;
+pub use detect::FormatProvider;
+pub use detect::FormatRegistry;
+pub use detect::sync_str;
+
+mod cargo;
+pub use cargo::read_cargo_version;
+pub use cargo::parse_cargo_version;
 
 mod error;
 pub use error::Error;
 
 pub type BuildGradleFile = GradleFile<BuildGradleContent>;
-pub type GradlePropertiesFile = GradleFile<PropertiesContent>;
\ No newline at end of file
+pub type BuildGradleKtsFile = GradleFile<BuildGradleKtsContent>;
+pub type GradlePropertiesFile = GradleFile<PropertiesContent>;
+pub type VersionCatalogFile = GradleFile<VersionCatalogContent>;
+pub type AndroidManifestFile = GradleFile<AndroidManifestContent>;
+pub type InfoPlistFile = GradleFile<InfoPlistContent>;
+pub type XcodeProjectFile = GradleFile<XcodeProjectContent>;
+pub type PubspecFile = GradleFile<PubspecContent>;
+pub type PackageJsonFile = GradleFile<PackageJsonContent>;
+pub type GradleProjectFile = GradleFile<GradleProjectContent>;
+pub type PomFile = GradleFile<PomContent>;
@@ -0,0 +1,98 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+use error::{Error, GradleResult};
+
+/// How long `GradleFile::new` waits for another process to release a file.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An advisory lock on a file, held as an OS lock (`flock`/`LockFileEx`) on
+/// `<file>.lock` next to it.
+///
+/// Every `GradleFile` opened for a sync holds the lock of its file until it
+/// is dropped, so build scripts syncing the same file in parallel do not
+/// read the file while another one is in the middle of updating it. The
+/// OS releases the lock when a process dies, so a `<file>.lock` left behind
+/// by a killed build is taken over by the next sync.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+    fd: File,
+}
+
+impl FileLock {
+    pub fn lock_path(filename: &str) -> PathBuf {
+        let mut path = Path::new(filename).as_os_str().to_owned();
+        path.push(".lock");
+        PathBuf::from(path)
+    }
+
+    /// Waits up to `timeout` for the lock of `filename`.
+    pub fn acquire(filename: &str, timeout: Duration) -> GradleResult<FileLock> {
+        if !Path::new(filename).is_file() {
+            let reason = format!("failed to read file: {}", filename);
+            return Err(Error::IoError(reason))
+        }
+        let path = FileLock::lock_path(filename);
+        let start = Instant::now();
+        loop {
+            let mut fd = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)
+                .map_err(|_err| {
+                    let reason = format!("failed to create lock file '{}'", path.display());
+                    Error::IoError(reason)
+                })?;
+            match fd.try_lock() {
+                // The previous holder removes the lock file before releasing
+                // it, so a lock on a file that is no longer at `path` is void.
+                Ok(()) if is_same_file(&fd, &path) => {
+                    let _ = fd.set_len(0).and_then(|_| write!(fd, "{}", process::id()));
+                    return Ok(FileLock { path, fd })
+                },
+                Ok(()) => continue,
+                Err(_) if start.elapsed() >= timeout => {
+                    let reason = format!(
+                        "timed out after {:?} waiting for the lock '{}' held by another sync",
+                        timeout, path.display());
+                    return Err(Error::LockTimeout(reason))
+                },
+                Err(_) => thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    }
+
+    /// Acquires the lock of `filename` and opens the file for reading.
+    pub(crate) fn open(filename: &str, timeout: Duration) -> GradleResult<(FileLock, File)> {
+        let lock = FileLock::acquire(filename, timeout)?;
+        let fd = File::open(filename)
+            .map_err(|_err| {
+                let reason = format!("failed to read file: {}", filename);
+                Error::IoError(reason)
+            })?;
+        Ok((lock, fd))
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(fd: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fd.metadata(), fs::metadata(path)) {
+        (Ok(locked), Ok(current)) => locked.dev() == current.dev() && locked.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_fd: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Removed while still locked, the lock is released when `fd` closes.
+        let _ = fs::remove_file(&self.path);
+        let _ = self.fd.unlock();
+    }
+}
@@ -0,0 +1,325 @@
+extern crate gradle_sync;
+extern crate semver;
+extern crate serde_json;
+
+// The binary is not part of the library, so its tests live next to the
+// library ones but are compiled with it.
+#[cfg(test)]
+#[path = "tests/cli.rs"]
+mod tests;
+
+use std::env;
+use std::fs::File;
+use std::process;
+use std::thread;
+use std::time::Duration;
+use semver::Version;
+use serde_json::{json, Value};
+use gradle_sync::{AnyGradleFile, ChangelogMode, DependencyContent, Error, GradleFile, ReleaseNotes, VersionPolicy, Watcher};
+
+const USAGE: &str = "Usage: gradle-sync [OPTIONS] <show|sync|check|watch> <FILE>...
+
+Commands:
+    show                          print the versions of the files
+    sync                          synchronize the files with the version
+    check                         fail when a file is out of sync
+    watch                         sync again whenever Cargo.toml or a file changes
+
+Options:
+    --format <text|json>          output format (default: text)
+    --version <VERSION>           version to synchronize with
+    --manifest-path <PATH>        Cargo.toml to read the version from (default: Cargo.toml)
+    --policy <error|warn|downgrade|exact>
+                                  what to do when the version decreases (default: error)
+    --dependency <GROUP:ARTIFACT> synchronize this dependency in the build scripts instead
+    --changelog <PATH>            release the [Unreleased] section of this changelog on sync,
+                                  require a section for the version on check
+    --release-notes <DIR>         create fastlane release notes for a new versionCode in DIR
+                                  (e.g. fastlane/metadata/android) from the changelog section
+    --locale <LOCALE>             locale to create release notes for (default: en-US)
+    --state <PATH>                record the versionCode of every synced version in PATH and
+                                  reuse it when the same version is synced again
+    --debounce <MS>               time the files have to stay unchanged before watch
+                                  syncs them (default: 500)
+    -h, --help                    print this help";
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+struct Options {
+    command: String,
+    format: OutputFormat,
+    version: Option<String>,
+    manifest_path: String,
+    policy: VersionPolicy,
+    dependency: Option<String>,
+    changelog: Option<String>,
+    release_notes: Option<String>,
+    locales: Vec<String>,
+    state: Option<String>,
+    debounce: Duration,
+    files: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: String::new(),
+        format: OutputFormat::Text,
+        version: None,
+        manifest_path: "Cargo.toml".to_string(),
+        policy: VersionPolicy::Error,
+        dependency: None,
+        changelog: None,
+        release_notes: None,
+        locales: vec!(),
+        state: None,
+        debounce: Duration::from_millis(500),
+        files: vec!(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next().cloned().ok_or_else(|| format!("missing value for {}", name))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Err(String::new()),
+            "--format" => {
+                options.format = match value(arg)?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown format '{}'", other)),
+                }
+            },
+            "--version" => options.version = Some(value(arg)?),
+            "--manifest-path" => options.manifest_path = value(arg)?,
+            "--policy" => {
+                options.policy = match value(arg)?.as_str() {
+                    "error" => VersionPolicy::Error,
+                    "warn" => VersionPolicy::WarnAndSkip,
+                    "downgrade" => VersionPolicy::AllowDowngrade,
+                    "exact" => VersionPolicy::ForceExact,
+                    other => return Err(format!("unknown policy '{}'", other)),
+                }
+            },
+            "--dependency" => options.dependency = Some(value(arg)?),
+            "--changelog" => options.changelog = Some(value(arg)?),
+            "--release-notes" => options.release_notes = Some(value(arg)?),
+            "--locale" => options.locales.push(value(arg)?),
+            "--state" => options.state = Some(value(arg)?),
+            "--debounce" => {
+                let debounce = value(arg)?;
+                options.debounce = debounce.parse().map(Duration::from_millis)
+                    .map_err(|_err| format!("invalid debounce '{}'", debounce))?;
+            },
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if options.command.is_empty() => options.command = arg.clone(),
+            _ => options.files.push(arg.clone()),
+        }
+    }
+    if options.command.is_empty() {
+        return Err("missing command".to_string())
+    }
+    if options.files.is_empty() {
+        return Err("missing files".to_string())
+    }
+    if options.locales.is_empty() {
+        options.locales.push("en-US".to_string());
+    }
+    Ok(options)
+}
+
+fn target_version(options: &Options) -> Result<Version, Error> {
+    match options.version {
+        Some(ref version) => gradle_sync::sem_version_parse(version),
+        None => gradle_sync::read_cargo_version(&options.manifest_path),
+    }
+}
+
+fn open_file(options: &Options, filename: &str) -> Result<AnyGradleFile, Error> {
+    match options.dependency {
+        Some(ref coordinate) => Ok(GradleFile::with_dependency(filename, coordinate)?.into_any()),
+        None => AnyGradleFile::new(filename),
+    }
+}
+
+/// Opens the file without locking it, for the commands that only read it.
+fn read_file(options: &Options, filename: &str) -> Result<AnyGradleFile, Error> {
+    let fd = File::open(filename)
+        .map_err(|_err| Error::IoError(format!("failed to read file: {}", filename)))?;
+    match options.dependency {
+        Some(ref coordinate) => {
+            let content = DependencyContent::parse(fd, coordinate)?;
+            Ok(GradleFile::with_content(filename, Box::new(content)))
+        },
+        None => AnyGradleFile::from_reader(filename, fd),
+    }
+}
+
+/// What running the command did to a single file.
+struct FileRun {
+    value: Value,
+    /// Whether the file is in the expected state.
+    ok: bool,
+    written: bool,
+}
+
+/// Runs the command on a single file.
+fn run_file(options: &Options, version: Option<&Version>, filename: &str) -> Result<FileRun, Error> {
+    let file = match version {
+        Some(_version) if options.command != "check" => open_file(options, filename)?,
+        _ => read_file(options, filename)?,
+    };
+    let mut file = file.with_policy(options.policy);
+    if let Some(ref changelog) = options.changelog {
+        file = file.with_changelog(changelog, ChangelogMode::Release);
+    }
+    if let Some(ref state) = options.state {
+        file = file.with_state_file(state);
+    }
+    if let Some(ref metadata_dir) = options.release_notes {
+        let mut release_notes = options.locales.iter()
+            .fold(ReleaseNotes::new(metadata_dir), |release_notes, locale| release_notes.locale(locale));
+        if let Some(ref changelog) = options.changelog {
+            release_notes = release_notes.with_changelog(changelog);
+        }
+        file = file.with_release_notes(release_notes);
+    }
+    let text = options.format == OutputFormat::Text;
+    match version {
+        Some(version) if options.command != "check" => {
+            let report = file.sync_and_write(version)?;
+            if text {
+                println!("{}", report);
+            }
+            let written = report.written;
+            Ok(FileRun { value: serde_json::to_value(report).unwrap(), ok: true, written })
+        },
+        Some(version) => {
+            let info = file.version_info()?;
+            let in_sync = match file.check_version(version) {
+                Err(Error::ChangelogEntryMissing(reason)) => return Err(Error::ChangelogEntryMissing(reason)),
+                result => result.is_ok(),
+            };
+            if text {
+                println!("{}{}", info, if in_sync { "" } else { " (out of sync)" });
+            }
+            let mut value = serde_json::to_value(info).unwrap();
+            value["in_sync"] = json!(in_sync);
+            Ok(FileRun { value, ok: in_sync, written: false })
+        },
+        None => {
+            let info = file.version_info()?;
+            if text {
+                println!("{}", info);
+            }
+            Ok(FileRun { value: serde_json::to_value(info).unwrap(), ok: true, written: false })
+        },
+    }
+}
+
+/// The outcome of running show, sync or check once: the JSON document, whether
+/// every file is in the expected state and the files that were written.
+struct Execution {
+    document: Value,
+    success: bool,
+    written: Vec<String>,
+}
+
+fn run(options: &Options, version: Option<&Version>) -> Execution {
+    let mut success = true;
+    let mut written = vec!();
+    let files: Vec<Value> = options.files.iter().map(|filename| {
+        match run_file(options, version, filename) {
+            Ok(run) => {
+                success &= run.ok;
+                if run.written {
+                    written.push(filename.clone());
+                }
+                run.value
+            },
+            Err(error) => {
+                success = false;
+                if options.format == OutputFormat::Text {
+                    eprintln!("{}: {}", filename, error);
+                }
+                json!({"filename": filename, "error": error.to_string()})
+            },
+        }
+    }).collect();
+    let mut document = json!({"command": options.command, "files": files});
+    if let Some(version) = version {
+        document["version"] = json!(version.to_string());
+    }
+    Execution { document, success, written }
+}
+
+/// Runs the command line and returns the exit code: 2 for a usage error, 1
+/// when a command fails and 0 otherwise. `watch` only returns on a usage error.
+fn run_cli(args: &[String]) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(reason) => {
+            if !reason.is_empty() {
+                eprintln!("error: {}\n", reason);
+            }
+            eprintln!("{}", USAGE);
+            return 2
+        },
+    };
+    let success = match options.command.as_str() {
+        "show" | "sync" | "check" => execute(&options).success,
+        "watch" => watch(&options),
+        other => {
+            eprintln!("error: unknown command '{}'\n\n{}", other, USAGE);
+            return 2
+        },
+    };
+    if success { 0 } else { 1 }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    process::exit(run_cli(&args));
+}
+
+/// Runs the command once and prints the result.
+fn execute(options: &Options) -> Execution {
+    let execution = match options.command.as_str() {
+        "show" => run(options, None),
+        _ => match target_version(options) {
+            Ok(version) => run(options, Some(&version)),
+            Err(error) => {
+                if options.format == OutputFormat::Text {
+                    eprintln!("error: {}", error);
+                }
+                let document = json!({"command": options.command, "error": error.to_string()});
+                Execution { document, success: false, written: vec!() }
+            },
+        },
+    };
+    if options.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&execution.document).unwrap());
+    }
+    execution
+}
+
+/// Syncs the files, then again whenever `Cargo.toml` or one of the files
+/// changes. The files written by a sync are taken as unchanged, so a sync
+/// never triggers the next one, while edits made during a sync still do.
+fn watch(options: &Options) -> bool {
+    let mut paths = options.files.clone();
+    if options.version.is_none() {
+        paths.push(options.manifest_path.clone());
+    }
+    let mut watcher = Watcher::new(&paths, options.debounce);
+    watcher.acknowledge(&execute(options).written);
+    loop {
+        thread::sleep(Duration::from_millis(100));
+        if watcher.poll() {
+            watcher.acknowledge(&execute(options).written);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use semver::Version;
+use regex::Regex;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+
+lazy_static! {
+    static ref VERSION_CODE_RE: Regex = Regex::new(r#"android:versionCode\s*=\s*"(?P<code>\d+)""#).unwrap();
+    static ref VERSION_NAME_RE: Regex = Regex::new(r#"android:versionName\s*=\s*"(?P<name>[0-9][0-9A-Za-z.+-]*)""#).unwrap();
+}
+
+pub struct AndroidManifestContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for AndroidManifestContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut version_code: Option<u32> = None;
+        let mut version_name: Option<Version> = None;
+        let mut spans = VersionSpans::default();
+
+        for caps in VERSION_CODE_RE.captures_iter(&source) {
+            let code = caps.name("code").unwrap();
+            if let Ok(parsed) = code.as_str().parse() {
+                version_code = version_code.or(Some(parsed));
+                spans.code.push(code.start()..code.end());
+            }
+        }
+        for caps in VERSION_NAME_RE.captures_iter(&source) {
+            let name = caps.name("name").unwrap();
+            if let Ok(parsed) = Version::parse(name.as_str()) {
+                version_name = version_name.or(Some(parsed));
+                spans.name.push(name.start()..name.end());
+            }
+        }
+        if version_code.is_none() {
+            return Err(Error::VersionNotFound("failed to find android:versionCode".to_string()))
+        }
+        if version_name.is_none() {
+            return Err(Error::VersionNotFound("failed to find android:versionName".to_string()))
+        }
+        Ok(Self{
+            source,
+            spans,
+            version: GradleVersion::new(
+                         version_code.unwrap(),
+                         version_name.unwrap()
+                         ),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
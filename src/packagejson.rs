@@ -0,0 +1,127 @@
+use semver::Version;
+use configfile::ConfigurationFormat;
+use std::io::Read;
+use std::ops::Range;
+use error::GradleResult;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+
+/// An npm `package.json` or `package-lock.json`.
+///
+/// Only the top-level `"version"` is read. In a lockfile the root package
+/// entry `"packages": {"": {"version": ...}}` is updated as well, while the
+/// versions of dependencies are left alone.
+pub struct PackageJsonContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for PackageJsonContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut version_name: Option<Version> = None;
+        let mut spans = VersionSpans::default();
+
+        for (path, range) in string_values(&source)? {
+            if path == ["version"] {
+                version_name = Some(Version::parse(&source[range.clone()]).map_err(|_err| {
+                    let reason = format!("failed to parse package version '{}'", &source[range.clone()]);
+                    Error::ParsingFailed(reason)
+                })?);
+                spans.name.push(range);
+            } else if path == ["packages", "", "version"] {
+                spans.name.push(range);
+            }
+        }
+        match version_name {
+            Some(version_name) => Ok(Self{
+                source,
+                spans,
+                version: GradleVersion::new(1, version_name),
+                modified: false
+            }),
+            None => Err(Error::VersionNotFound("failed to find top-level \"version\" in package.json".to_string())),
+        }
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn has_version_code(&self) -> bool {
+        false
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
+
+/// An object or array the scanner is in, with the key of the current
+/// member when it is an object.
+struct Container {
+    object: bool,
+    key: Option<String>,
+}
+
+/// Lists every string value of a JSON document with the object keys leading
+/// to it and the span of its content without quotes. Values inside arrays
+/// get `[]` as part of their path.
+fn string_values(source: &str) -> GradleResult<Vec<(Vec<String>, Range<usize>)>> {
+    let bytes = source.as_bytes();
+    let mut values = vec!();
+    let mut containers: Vec<Container> = vec!();
+    let mut expect_key = false;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'{' | b'[' => {
+                let object = bytes[pos] == b'{';
+                containers.push(Container { object, key: None });
+                expect_key = object;
+            },
+            b'}' | b']' => {
+                containers.pop();
+                expect_key = false;
+            },
+            b':' => expect_key = false,
+            b',' => expect_key = containers.last().map(|container| container.object).unwrap_or(false),
+            b'"' => {
+                let start = pos + 1;
+                pos = start;
+                while pos < bytes.len() && bytes[pos] != b'"' {
+                    pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+                if pos >= bytes.len() {
+                    return Err(Error::ParsingFailed("unterminated string in JSON".to_string()))
+                }
+                let text = &source[start..pos];
+                match containers.last_mut() {
+                    Some(container) if expect_key => container.key = Some(text.to_string()),
+                    _ => {
+                        let path = containers.iter()
+                            .map(|container| match container.key {
+                                Some(ref key) if container.object => key.clone(),
+                                _ => "[]".to_string(),
+                            })
+                            .collect();
+                        values.push((path, start..pos));
+                    },
+                }
+            },
+            _ => {},
+        }
+        pos += 1;
+    }
+    Ok(values)
+}
@@ -0,0 +1,93 @@
+use semver::Version;
+use regex::Regex;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions, parse_short_version};
+use span::{VersionSpans, read_source};
+
+lazy_static! {
+    static ref VERSION_CODE_RE: Regex = Regex::new(
+        r#"\bCURRENT_PROJECT_VERSION\s*=\s*"?(?P<code>\d+)"?\s*;"#).unwrap();
+    static ref VERSION_NAME_RE: Regex = Regex::new(
+        r#"\bMARKETING_VERSION\s*=\s*"?(?P<name>[0-9][0-9A-Za-z.+-]*)"?\s*;"#).unwrap();
+}
+
+/// The build settings of an Xcode `project.pbxproj`, with the versionName in
+/// `MARKETING_VERSION` and the versionCode in `CURRENT_PROJECT_VERSION`.
+///
+/// Every build configuration carries its own copy of the settings, all of
+/// them are written on a sync.
+pub struct XcodeProjectContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for XcodeProjectContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut version_code: Option<u32> = None;
+        let mut version_name: Option<Version> = None;
+        let mut invalid_name: Option<&str> = None;
+        let mut spans = VersionSpans::default();
+
+        for caps in VERSION_CODE_RE.captures_iter(&source) {
+            let code = caps.name("code").unwrap();
+            if let Ok(parsed) = code.as_str().parse() {
+                version_code = version_code.or(Some(parsed));
+                spans.code.push(code.start()..code.end());
+            }
+        }
+        for caps in VERSION_NAME_RE.captures_iter(&source) {
+            let name = caps.name("name").unwrap();
+            match parse_short_version(name.as_str()) {
+                Some((parsed, short)) => {
+                    if version_name.is_none() {
+                        version_name = Some(parsed);
+                        spans.short_name = short;
+                    }
+                    spans.name.push(name.start()..name.end());
+                },
+                None => invalid_name = invalid_name.or(Some(name.as_str())),
+            }
+        }
+        if version_code.is_none() {
+            return Err(Error::VersionNotFound("failed to find CURRENT_PROJECT_VERSION".to_string()))
+        }
+        if let (None, Some(name)) = (&version_name, invalid_name) {
+            let reason = format!("found MARKETING_VERSION {}, not a semantic version", name);
+            return Err(Error::VersionNotFound(reason))
+        }
+        if version_name.is_none() {
+            return Err(Error::VersionNotFound("failed to find MARKETING_VERSION".to_string()))
+        }
+        Ok(Self{
+            source,
+            spans,
+            version: GradleVersion::new(
+                         version_code.unwrap(),
+                         version_name.unwrap()
+                         ),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
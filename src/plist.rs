@@ -0,0 +1,112 @@
+use semver::Version;
+use regex::Regex;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions, parse_short_version};
+use span::{VersionSpans, read_source};
+
+lazy_static! {
+    static ref VERSION_CODE_RE: Regex = Regex::new(
+        r"<key>CFBundleVersion</key>\s*<string>(?P<code>[^<]*)</string>").unwrap();
+    static ref VERSION_NAME_RE: Regex = Regex::new(
+        r"<key>CFBundleShortVersionString</key>\s*<string>(?P<name>[^<]*)</string>").unwrap();
+}
+
+/// An iOS `Info.plist` in XML format, with the versionName in
+/// `CFBundleShortVersionString` and the versionCode in `CFBundleVersion`.
+pub struct InfoPlistContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+/// Fails when a value refers to a build setting, e.g. `$(MARKETING_VERSION)`,
+/// which has to be synchronized in `project.pbxproj` instead.
+fn check_build_setting(key: &str, value: &str) -> GradleResult<()> {
+    if value.contains("$(") || value.contains("${") {
+        let reason = format!(
+            "{} refers to the build setting {}, synchronize project.pbxproj instead",
+            key, value);
+        return Err(Error::UnsupportedSyntax(reason))
+    }
+    Ok(())
+}
+
+impl ConfigurationFormat for InfoPlistContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut version_code: Option<u32> = None;
+        let mut version_name: Option<Version> = None;
+        let mut invalid_code: Option<&str> = None;
+        let mut invalid_name: Option<&str> = None;
+        let mut spans = VersionSpans::default();
+
+        for caps in VERSION_CODE_RE.captures_iter(&source) {
+            let code = caps.name("code").unwrap();
+            check_build_setting("CFBundleVersion", code.as_str())?;
+            match code.as_str().trim().parse() {
+                Ok(parsed) => {
+                    version_code = version_code.or(Some(parsed));
+                    spans.code.push(code.start()..code.end());
+                },
+                Err(_) => invalid_code = invalid_code.or(Some(code.as_str().trim())),
+            }
+        }
+        for caps in VERSION_NAME_RE.captures_iter(&source) {
+            let name = caps.name("name").unwrap();
+            check_build_setting("CFBundleShortVersionString", name.as_str())?;
+            match parse_short_version(name.as_str().trim()) {
+                Some((parsed, short)) => {
+                    if version_name.is_none() {
+                        version_name = Some(parsed);
+                        spans.short_name = short;
+                    }
+                    spans.name.push(name.start()..name.end());
+                },
+                None => invalid_name = invalid_name.or(Some(name.as_str().trim())),
+            }
+        }
+        if let (None, Some(code)) = (&version_code, invalid_code) {
+            let reason = format!("found CFBundleVersion {}, not an integer versionCode", code);
+            return Err(Error::VersionNotFound(reason))
+        }
+        if version_code.is_none() {
+            return Err(Error::VersionNotFound("failed to find CFBundleVersion".to_string()))
+        }
+        if let (None, Some(name)) = (&version_name, invalid_name) {
+            let reason = format!("found CFBundleShortVersionString {}, not a semantic version", name);
+            return Err(Error::VersionNotFound(reason))
+        }
+        if version_name.is_none() {
+            return Err(Error::VersionNotFound("failed to find CFBundleShortVersionString".to_string()))
+        }
+        Ok(Self{
+            source,
+            spans,
+            version: GradleVersion::new(
+                         version_code.unwrap(),
+                         version_name.unwrap()
+                         ),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
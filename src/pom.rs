@@ -0,0 +1,110 @@
+use semver::Version;
+use configfile::ConfigurationFormat;
+use std::io::Read;
+use std::ops::Range;
+use error::GradleResult;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source};
+
+/// A Maven `pom.xml`, with the version in `<project><version>`.
+///
+/// The `<version>` elements of the parent, dependencies and plugins are left
+/// alone.
+pub struct PomContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for PomContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let range = match project_version(&source)? {
+            Some(range) => range,
+            None => return Err(Error::VersionNotFound("failed to find <project><version>".to_string())),
+        };
+        let raw = &source[range.clone()];
+        let text = raw.trim();
+        if text.contains("${") {
+            let reason = format!(
+                "<project><version> refers to the property {}, replace it with a literal version to synchronize it",
+                text);
+            return Err(Error::UnsupportedSyntax(reason))
+        }
+        let version_name = Version::parse(text).map_err(|_err| {
+            let reason = format!("failed to parse project version '{}'", text);
+            Error::ParsingFailed(reason)
+        })?;
+        let start = range.start + (raw.len() - raw.trim_start().len());
+        let mut spans = VersionSpans::default();
+        spans.name.push(start..(start + text.len()));
+        Ok(Self{
+            source,
+            spans,
+            version: GradleVersion::new(1, version_name),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn has_version_code(&self) -> bool {
+        false
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
+
+/// Finds the text of the `<version>` element that is a direct child of
+/// `<project>`, skipping comments, CDATA sections and processing
+/// instructions.
+fn project_version(source: &str) -> GradleResult<Option<Range<usize>>> {
+    let mut elements: Vec<&str> = vec!();
+    let mut pos = 0;
+    while let Some(offset) = source[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &source[start..];
+        let skip_to = |end: &str| {
+            rest.find(end).map(|index| start + index + end.len()).ok_or_else(|| {
+                Error::ParsingFailed(format!("unterminated '{}' in pom.xml", &rest[..rest.len().min(4)]))
+            })
+        };
+        if rest.starts_with("<!--") {
+            pos = skip_to("-->")?;
+        } else if rest.starts_with("<![CDATA[") {
+            pos = skip_to("]]>")?;
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            pos = skip_to(">")?;
+        } else {
+            let end = skip_to(">")?;
+            let tag = &source[start + 1..end - 1];
+            if let Some(name) = tag.strip_prefix('/') {
+                if elements.pop() != Some(name.trim()) {
+                    return Err(Error::ParsingFailed(format!("unexpected </{}> in pom.xml", name.trim())))
+                }
+            } else if !tag.ends_with('/') {
+                let name = tag.split(|c: char| c.is_whitespace()).next().unwrap_or("");
+                elements.push(name);
+                if elements == ["project", "version"] {
+                    let close = source[end..].find('<').map(|index| end + index).unwrap_or(source.len());
+                    return Ok(Some(end..close))
+                }
+            }
+            pos = end;
+        }
+    }
+    Ok(None)
+}
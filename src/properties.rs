@@ -2,35 +2,43 @@ use semver::Version;
 use regex::Regex;
 use configfile::ConfigurationFormat;
 use std::io::Read;
-use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use error::GradleResult;
 use error::Error;
-use version::GradleVersion;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source, line_offsets, offset_range};
+
+lazy_static! {
+    static ref PROJECT_VERSION_RE: Regex = Regex::new(r"projectVersion\s*=\s*(?P<version>[0-9][0-9A-Za-z.+-]*)").unwrap();
+}
 
 pub struct PropertiesContent {
-    lines: Vec<String>,
+    source: String,
+    spans: VersionSpans,
     version: GradleVersion,
     modified: bool
 }
 
 impl ConfigurationFormat for PropertiesContent {
     fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
         let mut project_version: Option<Version> = None;
+        let mut spans = VersionSpans::default();
 
-        let f = BufReader::new(reader);
-        let mut lines: Vec<String> = vec!();
-        for result_line in f.lines() {
-            let line = result_line?;
-            if project_version.is_none() {
-                project_version = parse_project_version_from_line(&line);
+        for (offset, line) in line_offsets(&source) {
+            if let Some((version, range)) = find_project_version(line) {
+                if project_version.is_none() {
+                    project_version = Some(version);
+                }
+                spans.name.push(offset_range(range, offset));
             }
-            lines.push(line);
         }
         if project_version.is_none() {
             return Err(Error::VersionNotFound("failed to find projectVersion".to_string()))
         }
         Ok(Self{
-            lines,
+            source,
+            spans,
             version: GradleVersion::new(1, project_version.unwrap()),
             modified: false
         })
@@ -41,40 +49,39 @@ impl ConfigurationFormat for PropertiesContent {
     fn is_modified(&self) -> bool {
         self.modified
     }
-    fn sync_version(&mut self, new_version: &Version) -> GradleResult<()> {
-        if self.version.synchronize_version(new_version)? {
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
             self.modified = true
         }
-        Ok(())
+        Ok(action)
+    }
+    fn has_version_code(&self) -> bool {
+        false
     }
-    fn lines(&self) -> Vec<String> {
-        self.lines.iter().map(|line|{
-            replace_project_version_from_line(line.clone(), self.version.version())
-        }).collect()
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
     }
 }
 
+fn find_project_version(line: &str) -> Option<(Version, Range<usize>)> {
+    let caps = PROJECT_VERSION_RE.captures(line)?;
+    let version = caps.name("version").unwrap();
+    Version::parse(version.as_str()).ok()
+        .map(|parsed| (parsed, version.start()..version.end()))
+}
+
 pub fn parse_project_version_from_line(line: &str) -> Option<Version> {
-    let re = Regex::new(r"projectVersion\s*=\s*(?P<version>[\d.]+)").unwrap();
-    let caps = re.captures(line);
-    match caps {
-        Some(caps) => {
-            let version = Version::parse(&caps["version"]);
-            match version {
-                Ok(version) => Some(version),
-                Err(_err) => None
-            }
-        },
-        None => None
-    }
+    find_project_version(line).map(|(version, _range)| version)
 }
 
 pub fn replace_project_version_from_line(line: String, new_version: &Version) -> String {
-    let version_found = parse_project_version_from_line(&line);
-    match version_found {
-        Some(old_version) => {
-            line.replace(&old_version.to_string(), &new_version.to_string())
+    match find_project_version(&line) {
+        Some((_version, range)) => {
+            let mut new_line = line.clone();
+            new_line.replace_range(range, &new_version.to_string());
+            new_line
         },
         None => line
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,97 @@
+use semver::Version;
+use regex::Regex;
+use configfile::ConfigurationFormat;
+use std::io::Read;
+use error::GradleResult;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source, line_offsets};
+
+lazy_static! {
+    static ref PUBSPEC_VERSION_RE: Regex = Regex::new(
+        r#"^version:\s*["']?(?P<name>[0-9][0-9A-Za-z.-]*)(?:\+(?P<code>\d+))?["']?\s*(?:#.*)?$"#).unwrap();
+}
+
+/// A Flutter `pubspec.yaml` with a top-level `version: 1.2.3+45`, where the
+/// build number after `+` is the versionCode.
+///
+/// Without a build number Flutter uses 1, and `+N` is appended once the
+/// version is synchronized. Build metadata of the synchronized version is
+/// dropped, as the build number takes its place.
+pub struct PubspecContent {
+    source: String,
+    spans: VersionSpans,
+    /// Where to append the build number when the file has none.
+    missing_code: Option<usize>,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for PubspecContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut spans = VersionSpans::default();
+        let mut missing_code = None;
+        let mut version = None;
+
+        for (offset, line) in line_offsets(&source) {
+            let caps = match PUBSPEC_VERSION_RE.captures(line) {
+                Some(caps) => caps,
+                None => continue,
+            };
+            let name = caps.name("name").unwrap();
+            let version_name = Version::parse(name.as_str()).map_err(|_err| {
+                let reason = format!("failed to parse pubspec version '{}'", name.as_str());
+                Error::ParsingFailed(reason)
+            })?;
+            spans.name.push((offset + name.start())..(offset + name.end()));
+            let version_code = match caps.name("code") {
+                Some(code) => {
+                    spans.code.push((offset + code.start())..(offset + code.end()));
+                    code.as_str().parse().map_err(|_err| {
+                        let reason = format!("failed to parse build number '{}'", code.as_str());
+                        Error::ParsingFailed(reason)
+                    })?
+                },
+                None => {
+                    missing_code = Some(offset + name.end());
+                    1
+                },
+            };
+            version = Some(GradleVersion::new(version_code, version_name));
+            break;
+        }
+        match version {
+            Some(version) => Ok(Self{
+                source,
+                spans,
+                missing_code,
+                version,
+                modified: false
+            }),
+            None => Err(Error::VersionNotFound("failed to find version in pubspec".to_string())),
+        }
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let mut new_version = new_version.clone();
+        new_version.build.clear();
+        let action = self.version.synchronize_with(&new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        let extra = match self.missing_code {
+            Some(offset) if self.modified => vec!((offset..offset, format!("+{}", self.version.code()))),
+            _ => vec!(),
+        };
+        self.spans.render_with(&self.source, &self.version, &extra)
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+use version::GradleVersion;
+
+/// A line rewritten by a synchronization, numbered from 1.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct ChangedLine {
+    pub line: usize,
+    pub old: String,
+    pub new: String,
+}
+
+/// The version currently found in a file. The versionCode is `None` for
+/// formats without one.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct VersionInfo {
+    pub filename: String,
+    pub version_name: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub version_code: Option<u32>,
+}
+
+impl VersionInfo {
+    pub fn new(filename: &str, version: &GradleVersion) -> VersionInfo {
+        VersionInfo {
+            filename: filename.to_string(),
+            version_name: version.version().to_string(),
+            version_code: Some(version.code()),
+        }
+    }
+
+    pub fn without_version_code(mut self) -> VersionInfo {
+        self.version_code = None;
+        self
+    }
+}
+
+impl fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: versionName {}", self.filename, self.version_name)?;
+        match self.version_code {
+            Some(version_code) => write!(f, ", versionCode {}", version_code),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a synchronization did to a single file. The versionCodes are `None`
+/// for formats without one.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
+pub struct SyncReport {
+    pub filename: String,
+    pub old_version_name: String,
+    pub new_version_name: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub old_version_code: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub new_version_code: Option<u32>,
+    pub written: bool,
+    pub changed_lines: Vec<ChangedLine>,
+    pub warnings: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn new(filename: &str, old_version: &GradleVersion, new_version: &GradleVersion) -> SyncReport {
+        SyncReport {
+            filename: filename.to_string(),
+            old_version_name: old_version.version().to_string(),
+            new_version_name: new_version.version().to_string(),
+            old_version_code: Some(old_version.code()),
+            new_version_code: Some(new_version.code()),
+            written: false,
+            changed_lines: vec!(),
+            warnings: vec!(),
+        }
+    }
+
+    pub fn without_version_code(mut self) -> SyncReport {
+        self.old_version_code = None;
+        self.new_version_code = None;
+        self
+    }
+
+    pub fn diff_lines(&mut self, old_lines: &[String], new_lines: &[String]) {
+        self.changed_lines = old_lines.iter().zip(new_lines.iter())
+            .enumerate()
+            .filter(|&(_, (old, new))| old != new)
+            .map(|(index, (old, new))| ChangedLine {
+                line: index + 1,
+                old: old.clone(),
+                new: new.clone(),
+            })
+            .collect();
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.old_version_name != self.new_version_name
+            || self.old_version_code != self.new_version_code
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.filename)?;
+        match (self.is_changed(), self.old_version_code, self.new_version_code) {
+            (true, Some(old_code), Some(new_code)) => write!(f, "versionName {} -> {}, versionCode {} -> {}",
+                   self.old_version_name, self.new_version_name, old_code, new_code)?,
+            (true, _, _) => write!(f, "versionName {} -> {}",
+                   self.old_version_name, self.new_version_name)?,
+            (false, _, Some(new_code)) => write!(f, "versionName {}, versionCode {} (unchanged)",
+                   self.new_version_name, new_code)?,
+            (false, _, None) => write!(f, "versionName {} (unchanged)", self.new_version_name)?,
+        }
+        if self.written {
+            write!(f, " (written)")?;
+        }
+        for changed_line in self.changed_lines.iter() {
+            write!(f, "\n  line {}: {} -> {}",
+                   changed_line.line, changed_line.old.trim(), changed_line.new.trim())?;
+        }
+        for warning in self.warnings.iter() {
+            write!(f, "\n  warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
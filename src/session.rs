@@ -0,0 +1,165 @@
+use std::env;
+use semver::Version;
+use configfile::{commit_all, ConfigurationFormat, GradleFile};
+use detect::AnyGradleFile;
+use error::{Error, GradleResult};
+use report::SyncReport;
+use version::sem_version_parse;
+
+/// What a `SyncSession` does when its files disagree on the version.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ConsistencyPolicy {
+    /// Sync every file from its own version.
+    #[default]
+    Independent,
+    /// Raise every file to the highest versionName and versionCode found
+    /// before syncing.
+    HighestWins,
+    /// Fail with `Error::VersionConflict`.
+    Error,
+}
+
+/// Syncs several files with all-or-nothing semantics.
+///
+/// Every file is parsed and synced in memory first. Only when all of them
+/// succeed are they written, otherwise `sync` fails with
+/// `Error::SyncFailed` listing every failure and no file is touched.
+#[derive(Default)]
+pub struct SyncSession {
+    files: Vec<AnyGradleFile>,
+    failures: Vec<(String, Error)>,
+    open_failures: Vec<(String, Error)>,
+    consistency: ConsistencyPolicy,
+}
+
+impl SyncSession {
+    pub fn new() -> SyncSession {
+        SyncSession::default()
+    }
+
+    /// Opens `filename` with the detected format. A file that fails to open
+    /// is reported by `sync` together with the other failures.
+    pub fn open(mut self, filename: &str) -> SyncSession {
+        match AnyGradleFile::new(filename) {
+            Ok(file) => self.files.push(file),
+            Err(error) => self.open_failures.push((filename.to_string(), error)),
+        }
+        self
+    }
+
+    pub fn with_file<T: ConfigurationFormat + 'static>(mut self, file: GradleFile<T>) -> SyncSession {
+        self.files.push(file.into_any());
+        self
+    }
+
+    pub fn with_consistency(mut self, consistency: ConsistencyPolicy) -> SyncSession {
+        self.consistency = consistency;
+        self
+    }
+
+    pub fn files(&self) -> &[AnyGradleFile] {
+        &self.files
+    }
+
+    /// The failures of the last `sync`, by file name.
+    pub fn failures(&self) -> &[(String, Error)] {
+        &self.failures
+    }
+
+    pub fn sync_with_cargo(&mut self) -> GradleResult<Vec<SyncReport>> {
+        let pkg_version = env::var("CARGO_PKG_VERSION").unwrap();
+        let pkg_version = sem_version_parse(&pkg_version)?;
+        self.sync(&pkg_version)
+    }
+
+    /// The highest versionName and versionCode among the files. Files
+    /// without a versionCode only count for the versionName.
+    fn highest_version(&self) -> GradleResult<Option<(Version, u32)>> {
+        let mut highest: Option<(Version, u32)> = None;
+        for file in &self.files {
+            let version = file.current_version()?;
+            let code = if file.has_version_code() { version.code() } else { 0 };
+            highest = Some(match highest {
+                Some((name, highest_code)) => (name.max(version.version().clone()), highest_code.max(code)),
+                None => (version.version().clone(), code),
+            });
+        }
+        Ok(highest)
+    }
+
+    /// Fails with `Error::VersionConflict` when the files disagree on the
+    /// versionName, or on the versionCode among the files that have one.
+    pub fn check_consistency(&self) -> GradleResult<()> {
+        let highest = match self.highest_version()? {
+            Some(highest) => highest,
+            None => return Ok(()),
+        };
+        let mut consistent = true;
+        let mut versions = vec!();
+        for file in &self.files {
+            let version = file.current_version()?;
+            consistent &= version.version() == &highest.0
+                && (!file.has_version_code() || version.code() == highest.1);
+            versions.push(if file.has_version_code() {
+                format!("{} {} (versionCode {})", file.filename(), version.version(), version.code())
+            } else {
+                format!("{} {}", file.filename(), version.version())
+            });
+        }
+        if !consistent {
+            let reason = format!("files disagree on the version: {}", versions.join(", "));
+            return Err(Error::VersionConflict(reason))
+        }
+        Ok(())
+    }
+
+    /// Raises every file to the highest version in memory, returning the
+    /// files that changed.
+    fn reconcile(&mut self) -> GradleResult<Vec<String>> {
+        let (name, code) = match self.highest_version()? {
+            Some(highest) => highest,
+            None => return Ok(vec!()),
+        };
+        let mut reconciled = vec!();
+        for file in self.files.iter_mut() {
+            let file_code = if file.has_version_code() { code } else { file.current_version()?.code() };
+            if file.reconcile(&name, file_code)? {
+                reconciled.push(file.filename().to_string());
+            }
+        }
+        Ok(reconciled)
+    }
+
+    pub fn sync(&mut self, new_version: &Version) -> GradleResult<Vec<SyncReport>> {
+        self.failures = self.open_failures.clone();
+        let reconciled = match self.consistency {
+            ConsistencyPolicy::Independent => vec!(),
+            ConsistencyPolicy::HighestWins => self.reconcile()?,
+            ConsistencyPolicy::Error => {
+                self.check_consistency()?;
+                vec!()
+            },
+        };
+        let mut prepared = vec!();
+        for file in self.files.iter_mut() {
+            match file.prepare_sync(new_version) {
+                Ok(sync) => prepared.push(sync),
+                Err(error) => self.failures.push((file.filename().to_string(), error)),
+            }
+        }
+        if !self.failures.is_empty() {
+            let mut reason = format!(
+                "{} of {} files failed to sync, no file was written:",
+                self.failures.len(), self.files.len() + self.open_failures.len());
+            for (filename, error) in &self.failures {
+                reason.push_str(&format!("\n  {}: {}", filename, error));
+            }
+            return Err(Error::SyncFailed(reason))
+        }
+        let mut reports = commit_all(&mut self.files, prepared)?;
+        for report in reports.iter_mut().filter(|report| reconciled.contains(&report.filename)) {
+            report.warnings.push("raised to the highest version of the session before syncing".to_string());
+        }
+        Ok(reports)
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::Read;
+use std::ops::Range;
+use error::GradleResult;
+use version::GradleVersion;
+
+/// The byte ranges of the versionCode and versionName tokens in a file.
+///
+/// Writing a file only replaces these ranges, every other byte is kept as is.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct VersionSpans {
+    pub code: Vec<Range<usize>>,
+    pub name: Vec<Range<usize>>,
+    /// Writes versionNames ending in `.0` as `major.minor`, for files that
+    /// used that form.
+    pub short_name: bool,
+}
+
+impl VersionSpans {
+    pub fn render(&self, source: &str, version: &GradleVersion) -> String {
+        self.render_with(source, version, &[])
+    }
+
+    /// Renders the file with additional, non-overlapping edits.
+    pub fn render_with(&self, source: &str, version: &GradleVersion, extra: &[(Range<usize>, String)]) -> String {
+        let version_code = version.code().to_string();
+        let name = version.version();
+        let version_name = if self.short_name && name.patch == 0 && name.pre.is_empty() && name.build.is_empty() {
+            format!("{}.{}", name.major, name.minor)
+        } else {
+            name.to_string()
+        };
+        let mut edits: Vec<(&Range<usize>, &str)> = self.code.iter()
+            .map(|range| (range, version_code.as_str()))
+            .chain(self.name.iter().map(|range| (range, version_name.as_str())))
+            .chain(extra.iter().map(|edit| (&edit.0, edit.1.as_str())))
+            .collect();
+        edits.sort_by_key(|edit| edit.0.start);
+
+        let mut content = String::with_capacity(source.len());
+        let mut pos = 0;
+        for (range, replacement) in edits {
+            content.push_str(&source[pos..range.start]);
+            content.push_str(replacement);
+            pos = range.end;
+        }
+        content.push_str(&source[pos..]);
+        content
+    }
+}
+
+pub fn read_source<R: Read>(mut reader: R) -> GradleResult<String> {
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+    Ok(source)
+}
+
+/// Yields every line of `source` (without the line ending) together with
+/// the byte offset where it starts.
+pub fn line_offsets(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    source.split('\n').map(move |line| {
+        let start = offset;
+        offset += line.len() + 1;
+        (start, line.trim_end_matches('\r'))
+    })
+}
+
+/// Moves a range found within a line to the whole file.
+pub fn offset_range(range: Range<usize>, offset: usize) -> Range<usize> {
+    (range.start + offset)..(range.end + offset)
+}
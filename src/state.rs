@@ -0,0 +1,105 @@
+use semver::Version;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use error::{Error, GradleResult};
+use lock::{FileLock, DEFAULT_LOCK_TIMEOUT};
+use version::GradleVersion;
+
+/// The versionCode every synced versionName got, per file.
+///
+/// The state file has one `<file>\t<versionName>\t<versionCode>` line per
+/// sync, so syncing the same version again, e.g. after reverting the build
+/// script, always yields the same versionCode. Files are keyed by their path
+/// as given, without `.` components, so `./app/build.gradle` and
+/// `app/build.gradle` share their entries but an absolute path does not.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct VersionState {
+    entries: Vec<(String, Version, u32)>,
+}
+
+impl VersionState {
+    /// Reads the state file, which is empty when it does not exist yet.
+    pub fn open(filename: &str) -> GradleResult<VersionState> {
+        let source = match fs::read_to_string(filename) {
+            Ok(source) => source,
+            Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(VersionState::default()),
+            Err(_) => {
+                let reason = format!("failed to read file: {}", filename);
+                return Err(Error::IoError(reason))
+            },
+        };
+        VersionState::parse(filename, &source)
+    }
+
+    pub fn parse(filename: &str, source: &str) -> GradleResult<VersionState> {
+        let mut state = VersionState::default();
+        for (index, line) in source.lines().enumerate().filter(|line| !line.1.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let entry = match fields.as_slice() {
+                [file, name, code] => Version::parse(name).ok()
+                    .and_then(|name| code.parse().ok().map(|code| (file.to_string(), name, code))),
+                _ => None,
+            };
+            match entry {
+                Some(entry) => state.entries.push(entry),
+                None => {
+                    let reason = format!("invalid entry on line {} of '{}'", index + 1, filename);
+                    return Err(Error::ParsingFailed(reason))
+                },
+            }
+        }
+        Ok(state)
+    }
+
+    /// The versionCode `file` got when it was synced with `version`.
+    pub fn version_code(&self, file: &str, version: &Version) -> Option<u32> {
+        let file = state_key(file);
+        self.entries.iter()
+            .find(|entry| entry.0 == file && &entry.1 == version)
+            .map(|entry| entry.2)
+    }
+
+    pub fn record(&mut self, file: &str, version: &GradleVersion) {
+        let file = state_key(file);
+        self.entries.retain(|entry| !(entry.0 == file && &entry.1 == version.version()));
+        self.entries.push((file, version.version().clone(), version.code()));
+    }
+
+    pub fn render(&self) -> String {
+        self.entries.iter()
+            .map(|entry| format!("{}\t{}\t{}\n", entry.0, entry.1, entry.2))
+            .collect()
+    }
+
+    /// Records `version` for `file` in the state file, holding its lock so
+    /// syncs of other files sharing the state file are not lost.
+    pub fn record_synced(filename: &str, file: &str, version: &GradleVersion) -> GradleResult<()> {
+        if !Path::new(filename).exists() {
+            OpenOptions::new().write(true).create(true).truncate(false).open(filename)
+                .map_err(|_err| {
+                    let reason = format!("failed to create file: {}", filename);
+                    Error::IoError(reason)
+                })?;
+        }
+        let _lock = FileLock::acquire(filename, DEFAULT_LOCK_TIMEOUT)?;
+        let mut state = VersionState::open(filename)?;
+        if state.version_code(file, version.version()) == Some(version.code()) {
+            return Ok(())
+        }
+        state.record(file, version);
+        fs::write(filename, state.render())
+            .map_err(|_err| {
+                let reason = format!("failed to write file: {}", filename);
+                Error::IoError(reason)
+            })
+    }
+}
+
+/// The path of `file` without `.` components, as used in the state file.
+fn state_key(file: &str) -> String {
+    let path: PathBuf = Path::new(file).components()
+        .filter(|component| component != &Component::CurDir)
+        .collect();
+    path.to_string_lossy().into_owned()
+}
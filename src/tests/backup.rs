@@ -0,0 +1,107 @@
+use std::env;
+use std::fs;
+use std::process;
+use semver::Version;
+
+use buildgradle::BuildGradleContent;
+use configfile::{GradleFile, sync_all};
+use error::Error;
+
+#[test]
+fn should_back_up_and_roll_back() {
+    let path = env::temp_dir().join(format!("gradle-sync-backup-{}.gradle", process::id()));
+    let filename = path.to_str().unwrap();
+    fs::write(filename, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+
+    let mut file = GradleFile::<BuildGradleContent>::new(filename).unwrap().with_backup();
+    assert_eq!(file.rollback(), Err(Error::IoError(format!("no backup of '{}' to roll back to", filename))));
+    file.sync_and_write(&Version::parse("0.2.0").unwrap()).unwrap();
+    assert_eq!(fs::read_to_string(file.backup_path()).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    assert_eq!(fs::read_to_string(filename).unwrap(), "versionCode 2\nversionName \"0.2.0\"\n");
+
+    file.rollback().unwrap();
+    assert!(!file.backup_path().exists());
+    assert_eq!(fs::read_to_string(filename).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    drop(file);
+    fs::remove_file(filename).unwrap();
+}
+
+#[test]
+fn should_not_write_any_file_when_a_later_one_fails_to_sync() {
+    let first = env::temp_dir().join(format!("gradle-sync-first-{}.gradle", process::id()));
+    let second = env::temp_dir().join(format!("gradle-sync-second-{}.gradle", process::id()));
+    let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+    fs::write(first, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(second, "versionCode 3\nversionName \"0.3.0\"\n").unwrap();
+
+    let mut files = vec!(
+        GradleFile::<BuildGradleContent>::new(first).unwrap(),
+        GradleFile::<BuildGradleContent>::new(second).unwrap(),
+    );
+    let result = sync_all(&mut files, &Version::parse("0.2.0").unwrap());
+    assert_eq!(result.err().unwrap(), Error::VersionNotIncreasing(
+        "version not increasing (old)0.3.0 > (new)0.2.0".to_string()));
+    assert_eq!(fs::read_to_string(first).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    assert!(!files[0].backup_path().exists());
+
+    let reports = sync_all(&mut files[..1], &Version::parse("0.2.0").unwrap()).unwrap();
+    assert!(reports[0].written);
+    assert!(!files[0].backup_path().exists());
+    drop(files);
+    fs::remove_file(first).unwrap();
+    fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn should_restore_written_files_when_a_later_write_fails() {
+    let first = env::temp_dir().join(format!("gradle-sync-written-{}.gradle", process::id()));
+    let second = env::temp_dir().join(format!("gradle-sync-unwritable-{}.gradle", process::id()));
+    let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+    fs::write(first, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(second, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+
+    let mut files = vec!(
+        GradleFile::<BuildGradleContent>::new(first).unwrap(),
+        GradleFile::<BuildGradleContent>::new(second).unwrap(),
+    );
+    // Read and synced fine, but replaced by a directory before it is written.
+    fs::remove_file(second).unwrap();
+    fs::create_dir(second).unwrap();
+
+    let result = sync_all(&mut files, &Version::parse("0.2.0").unwrap());
+    assert_eq!(result.err().unwrap(), Error::IoError(format!("failed to back up '{}'", second)));
+    assert_eq!(fs::read_to_string(first).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    assert!(!files[0].backup_path().exists());
+    assert!(!files[1].backup_path().exists());
+    drop(files);
+    fs::remove_file(first).unwrap();
+    fs::remove_dir(second).unwrap();
+}
+
+#[test]
+fn should_report_files_that_fail_to_roll_back() {
+    let first = env::temp_dir().join(format!("gradle-sync-twice-{}.gradle", process::id()));
+    let second = env::temp_dir().join(format!("gradle-sync-failing-{}.gradle", process::id()));
+    let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+    fs::write(first, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(second, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+
+    // The same file twice: restoring the first copy consumes the backup the
+    // second copy needs.
+    let mut files = vec!(
+        GradleFile::<BuildGradleContent>::new(first).unwrap(),
+        GradleFile::<BuildGradleContent>::from_reader(first, fs::File::open(first).unwrap()).unwrap(),
+        GradleFile::<BuildGradleContent>::new(second).unwrap(),
+    );
+    fs::remove_file(second).unwrap();
+    fs::create_dir(second).unwrap();
+
+    let result = sync_all(&mut files, &Version::parse("0.2.0").unwrap());
+    assert_eq!(result.err().unwrap(), Error::SyncFailed(format!(
+        "failed to back up '{}', and failed to roll back:\n  {}: failed to restore '{}' from its backup",
+        second, first, first)));
+    assert!(!files[0].backup_path().exists());
+    drop(files);
+    fs::remove_file(first).unwrap();
+    fs::remove_dir(second).unwrap();
+}
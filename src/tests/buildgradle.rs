@@ -7,6 +7,7 @@ use buildgradle::replace_version_name;
 use buildgradle::BuildGradleContent;
 use configfile::ConfigurationFormat;
 use error::Error;
+use version::{AbiSplits, SemverCode, SyncOptions, VersionCodeStrategy};
 
 #[test]
 fn parse_version_code_in_line() {
@@ -109,7 +110,7 @@ fn read_gradle_version_from_file_content() {
     let expected_version_name = Version::parse("1.1.2").unwrap();
 
     let content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
-    assert_eq!(content.is_modified(), false);
+    assert!(!content.is_modified());
     let version = content.current_version().unwrap();
     assert_eq!(version.code(), expected_code);
     assert_eq!(version.version(), &expected_version_name);
@@ -158,11 +159,11 @@ fn content_should_only_be_modified_when_synchronized_with_higher_version_number(
     let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
     content.sync_version(&same_version)
         .expect("failed to synchronize version");
-    assert_eq!(content.is_modified(), false);
+    assert!(!content.is_modified());
 
     content.sync_version(&higher_version)
         .expect("failed to synchronize version");
-    assert_eq!(content.is_modified(), true);
+    assert!(content.is_modified());
 }
 
 #[test]
@@ -181,7 +182,7 @@ fn should_write_new_version_to_writer() {
             versionCode 3
             versionName \"1.2.0\"
         }
-    }\n";
+    }";
 
     let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
     content.sync_version(&new_version)
@@ -191,3 +192,246 @@ fn should_write_new_version_to_writer() {
         .expect("failed to write content to writer");
     assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
 }
+
+#[test]
+fn should_ignore_version_in_comments_and_strings() {
+    let file_content = "
+    // versionCode 1
+    /* versionName \"0.0.1\" */
+    android {
+        defaultConfig {
+            versionCode 2 // versionCode 3
+            versionName \"1.1.2\"
+        }
+    }
+    println \"versionCode 3\"".as_bytes();
+    let new_version = Version::parse("1.2.0").unwrap();
+    let expected_file_content = "
+    // versionCode 1
+    /* versionName \"0.0.1\" */
+    android {
+        defaultConfig {
+            versionCode 3 // versionCode 3
+            versionName \"1.2.0\"
+        }
+    }
+    println \"versionCode 3\"";
+
+    let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 2);
+    assert_eq!(version.version().to_string(), "1.1.2");
+
+    content.sync_version(&new_version)
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_leave_product_flavor_overrides_alone() {
+    let file_content = "
+    android {
+        defaultConfig {
+            versionCode 2
+            versionName \"1.1.2\"
+        }
+        productFlavors {
+            free { versionCode 100; versionName \"9.0.0\" }
+        }
+    }".as_bytes();
+    let new_version = Version::parse("1.2.0").unwrap();
+    let expected_file_content = "
+    android {
+        defaultConfig {
+            versionCode 3
+            versionName \"1.2.0\"
+        }
+        productFlavors {
+            free { versionCode 100; versionName \"9.0.0\" }
+        }
+    }";
+
+    let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&new_version)
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_not_find_version_code_in_line_comment() {
+    assert!(parse_version_code_line("// versionCode 2").is_none());
+    assert!(parse_version_name_line("println \"versionName \\\"1.0.0\\\"\"").is_none());
+    assert_eq!(replace_version_code("// versionCode 2".to_string(), 3), "// versionCode 2");
+}
+
+#[test]
+fn parse_all_groovy_syntaxes() {
+    let code_lines = ["versionCode = 5", "versionCode(5)", "versionCode 5;", "versionCode( 5 )"];
+    code_lines.iter().for_each(|line| {
+        assert_eq!(parse_version_code_line(line), Some(5), "{}", line);
+    });
+    let name_lines = [
+        "versionName '1.2.3'",
+        "versionName = \"1.2.3\"",
+        "versionName('1.2.3')",
+        "versionName = '''1.2.3'''",
+    ];
+    name_lines.iter().for_each(|line| {
+        assert_eq!(parse_version_name_line(line).unwrap().to_string(), "1.2.3", "{}", line);
+    });
+}
+
+#[test]
+fn replace_keeps_quote_style_and_spacing() {
+    let version = Version::parse("2.0.0").unwrap();
+    let cases = [
+        ["versionName '1.2.3'", "versionName '2.0.0'"],
+        ["versionName  =  \"1.2.3\"", "versionName  =  \"2.0.0\""],
+        ["versionName('1.2.3')", "versionName('2.0.0')"],
+    ];
+    cases.iter().for_each(|case| {
+        assert_eq!(replace_version_name(case[0].to_string(), &version), case[1]);
+    });
+    assert_eq!(replace_version_code("versionCode(12)".to_string(), 13), "versionCode(13)");
+    assert_eq!(replace_version_code("versionCode = 12".to_string(), 13), "versionCode = 13");
+}
+
+#[test]
+fn fail_with_actionable_error_on_interpolated_version_name() {
+    let file_content = "
+    android {
+        defaultConfig {
+            versionCode = 2
+            versionName \"${major}.${minor}.0\"
+        }
+    }".as_bytes();
+
+    let content = <BuildGradleContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "versionName on line 5 is an interpolated GString (\"${major}.${minor}.0\"), \
+             replace it with a literal version such as \"1.0.0\" to synchronize it".to_string()));
+}
+
+#[test]
+fn fail_on_unterminated_version_name() {
+    assert_eq!(parse_version_name_line("versionName \"1é"), None);
+
+    let file_content = "
+    android {
+        defaultConfig {
+            versionCode 2
+            versionName \"1.0.é
+        }
+    }".as_bytes();
+    let content = <BuildGradleContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::ParsingFailed(
+            "versionName on line 5 is an unterminated string".to_string()));
+}
+
+#[test]
+fn fail_on_version_code_outside_play_range() {
+    let file_content = "versionCode 99999999999\nversionName \"1.0.0\"\n".as_bytes();
+    let content = <BuildGradleContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::InvalidVersionCode(
+            "versionCode 99999999999 on line 1 is outside the Google Play range 1..=2100000000".to_string()));
+
+    for code in &["0", "2200000000"] {
+        let file_content = format!("versionCode {}\nversionName \"1.0.0\"\n", code);
+        let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content.as_bytes()).unwrap();
+        assert_eq!(content.sync_version(&Version::parse("1.0.0").unwrap()), Err(Error::InvalidVersionCode(format!(
+            "current versionCode {} is outside the Google Play range 1..=2100000000", code))));
+    }
+}
+
+#[test]
+fn should_write_new_version_with_assignment_syntax() {
+    let file_content = "
+    android {
+        defaultConfig {
+            versionCode = 2
+            versionName = '1.1.2'
+        }
+    }".as_bytes();
+    let expected_file_content = "
+    android {
+        defaultConfig {
+            versionCode = 3
+            versionName = '1.2.0'
+        }
+    }";
+
+    let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.2.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_derive_version_code_and_rewrite_abi_codes() {
+    let file_content = "
+    ext.abiCodes = ['armeabi-v7a': 1, 'arm64-v8a': 2]
+    ext.abiCodeMultiplier = 10
+    android {
+        defaultConfig {
+            versionCode 10100
+            versionName \"1.1.0\"
+        }
+    }".as_bytes();
+    let expected_file_content = "
+    ext.abiCodes = ['armeabi-v7a': 3, 'arm64-v8a': 4]
+    ext.abiCodeMultiplier = 100
+    android {
+        defaultConfig {
+            versionCode 10203
+            versionName \"1.2.3\"
+        }
+    }";
+    let abi_splits = AbiSplits::new(100).abi("armeabi-v7a", 3).abi("arm64-v8a", 4);
+    let options = SyncOptions {
+        code_strategy: VersionCodeStrategy::Semver(SemverCode::default().with_abi_splits(abi_splits)),
+        ..SyncOptions::default()
+    };
+
+    let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version_with(&Version::parse("1.2.3").unwrap(), &options)
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), expected_file_content);
+}
+
+#[test]
+fn should_rewrite_abi_multiplier_in_version_code_expression() {
+    let file_content = "
+    ext.abiCodes = ['armeabi-v7a': 1, 'arm64-v8a': 2]
+    android {
+        defaultConfig {
+            versionCode 10100
+            versionName \"1.1.0\"
+        }
+        applicationVariants.all { variant ->
+            variant.outputs.each { output ->
+                output.versionCodeOverride = variant.versionCode * 10 + project.ext.abiCodes.get(output.getFilter(OutputFile.ABI))
+            }
+        }
+    }".as_bytes();
+    let abi_splits = AbiSplits::new(100).abi("armeabi-v7a", 1).abi("arm64-v8a", 2);
+    let options = SyncOptions {
+        code_strategy: VersionCodeStrategy::Semver(SemverCode::default().with_abi_splits(abi_splits)),
+        ..SyncOptions::default()
+    };
+
+    let mut content = <BuildGradleContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version_with(&Version::parse("1.2.0").unwrap(), &options)
+        .expect("failed to synchronize version");
+    let rendered = content.render();
+    assert!(rendered.contains("versionCode 10200\n"));
+    assert!(rendered.contains("variant.versionCode * 100 + project.ext.abiCodes.get("));
+}
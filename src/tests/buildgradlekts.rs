@@ -0,0 +1,73 @@
+use semver::Version;
+
+use buildgradlekts::BuildGradleKtsContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_kts_version() {
+    let file_content = "
+    android {
+        defaultConfig {
+            // versionCode = 1
+            versionCode = 2
+            versionName = \"1.2.3\"
+        }
+    }".as_bytes();
+
+    let content = <BuildGradleKtsContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 2);
+    assert_eq!(version.version().to_string(), "1.2.3");
+}
+
+#[test]
+fn fail_if_kts_file_does_not_contain_version_code() {
+    let file_content = "
+    android {
+        defaultConfig {
+            versionName = \"1.1.2\"
+        }
+    }".as_bytes();
+
+    let content = <BuildGradleKtsContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find versionCode".to_string()));
+}
+
+#[test]
+fn fail_on_string_template_version_name() {
+    let file_content = "versionCode = 2\nversionName = \"$major.0.0\"".as_bytes();
+
+    let content = <BuildGradleKtsContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "versionName on line 2 is an interpolated string template (\"$major.0.0\"), \
+             replace it with a literal version such as \"1.0.0\" to synchronize it".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = "
+    android {
+        defaultConfig {
+            versionCode = 2
+            versionName = \"1.1.2\"
+        }
+    }".as_bytes();
+    let new_version = Version::parse("1.2.0").unwrap();
+    let expected_file_content = "
+    android {
+        defaultConfig {
+            versionCode = 3
+            versionName = \"1.2.0\"
+        }
+    }";
+
+    let mut content = <BuildGradleKtsContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&new_version)
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
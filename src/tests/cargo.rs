@@ -0,0 +1,27 @@
+use cargo::parse_cargo_version;
+use error::Error;
+
+#[test]
+fn should_read_package_version() {
+    let file_content = "
+[dependencies]
+version = \"9.9.9\"
+
+[package]
+name = \"demo\"
+version = \"1.4.2\"
+".as_bytes();
+    let version = parse_cargo_version(file_content).unwrap();
+    assert_eq!(version.to_string(), "1.4.2");
+}
+
+#[test]
+fn should_fail_without_package_version() {
+    let file_content = "
+[package]
+name = \"demo\"
+".as_bytes();
+    let error = parse_cargo_version(file_content).err().unwrap();
+    assert_eq!(error, Error::VersionNotFound("failed to find the package version".to_string()));
+    assert_eq!(error.to_string(), "failed to find the package version");
+}
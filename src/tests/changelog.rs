@@ -0,0 +1,73 @@
+use semver::Version;
+
+use changelog::{Changelog, today};
+use error::Error;
+
+const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+### Added
+- Sync pubspec.yaml
+
+## [1.1.0] - 2024-01-02
+### Fixed
+- Keep CRLF line endings
+
+[Unreleased]: https://github.com/example/core/compare/v1.1.0...HEAD
+[1.1.0]: https://github.com/example/core/compare/v1.0.0...v1.1.0
+";
+
+#[test]
+fn should_release_unreleased_entries() {
+    let mut changelog = Changelog::from_reader("CHANGELOG.md", CHANGELOG.as_bytes()).unwrap();
+    changelog.release(&Version::parse("1.2.0").unwrap(), "2024-03-04").unwrap();
+
+    assert!(changelog.is_modified());
+    assert_eq!(changelog.content(), "# Changelog
+
+## [Unreleased]
+
+## [1.2.0] - 2024-03-04
+### Added
+- Sync pubspec.yaml
+
+## [1.1.0] - 2024-01-02
+### Fixed
+- Keep CRLF line endings
+
+[Unreleased]: https://github.com/example/core/compare/v1.2.0...HEAD
+[1.2.0]: https://github.com/example/core/compare/v1.1.0...v1.2.0
+[1.1.0]: https://github.com/example/core/compare/v1.0.0...v1.1.0
+");
+
+    let content = changelog.content().to_string();
+    changelog.release(&Version::parse("1.2.0").unwrap(), "2024-03-05").unwrap();
+    assert_eq!(changelog.content(), content);
+}
+
+#[test]
+fn should_fail_without_unreleased_entries() {
+    let mut changelog = Changelog::from_reader(
+        "CHANGELOG.md", "## [Unreleased]\n### Added\n\n## [1.1.0] - 2024-01-02\n- Fix\n".as_bytes()).unwrap();
+    assert_eq!(changelog.release(&Version::parse("1.2.0").unwrap(), "2024-03-04"),
+        Err(Error::ChangelogEntryMissing(
+            "no entries under [Unreleased] in 'CHANGELOG.md' to release as 1.2.0".to_string())));
+    assert!(!changelog.is_modified());
+}
+
+#[test]
+fn should_check_version_section() {
+    let changelog = Changelog::from_reader("CHANGELOG.md", CHANGELOG.as_bytes()).unwrap();
+    assert_eq!(changelog.check(&Version::parse("1.1.0").unwrap()), Ok(()));
+    assert_eq!(changelog.check(&Version::parse("1.2.0").unwrap()),
+        Err(Error::ChangelogEntryMissing("no section for 1.2.0 in 'CHANGELOG.md'".to_string())));
+}
+
+#[test]
+fn should_format_today() {
+    let date = today();
+    assert_eq!(date.len(), 10);
+    assert_eq!(&date[4..5], "-");
+    assert_eq!(&date[7..8], "-");
+    assert!(date.as_str() >= "2024-01-01");
+}
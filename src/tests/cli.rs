@@ -0,0 +1,162 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+use serde_json::json;
+
+use gradle_sync::{VersionPolicy, Watcher};
+use {execute, parse_args, run_cli, OutputFormat};
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn project(name: &str) -> (PathBuf, String, String) {
+    let dir = env::temp_dir().join(format!("gradle-sync-cli-{}-{}", name, process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let build_gradle = dir.join("build.gradle").to_str().unwrap().to_string();
+    let properties = dir.join("gradle.properties").to_str().unwrap().to_string();
+    fs::write(&build_gradle, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(&properties, "projectVersion=0.1.0\n").unwrap();
+    (dir, build_gradle, properties)
+}
+
+#[test]
+fn should_parse_arguments() {
+    let options = parse_args(&args(&["--format", "json", "--policy", "warn", "sync", "a.gradle", "b.gradle"])).unwrap();
+    assert_eq!(options.command, "sync");
+    assert!(options.format == OutputFormat::Json);
+    assert_eq!(options.policy, VersionPolicy::WarnAndSkip);
+    assert_eq!(options.manifest_path, "Cargo.toml");
+    assert_eq!(options.locales, vec!("en-US"));
+    assert_eq!(options.files, vec!("a.gradle", "b.gradle"));
+
+    assert_eq!(parse_args(&args(&["-h"])).err(), Some(String::new()));
+    assert_eq!(parse_args(&args(&[])).err(), Some("missing command".to_string()));
+    assert_eq!(parse_args(&args(&["show"])).err(), Some("missing files".to_string()));
+    assert_eq!(parse_args(&args(&["show", "a.gradle", "--version"])).err(),
+               Some("missing value for --version".to_string()));
+    assert_eq!(parse_args(&args(&["--format", "xml", "show", "a.gradle"])).err(),
+               Some("unknown format 'xml'".to_string()));
+    assert_eq!(parse_args(&args(&["--verbose", "show", "a.gradle"])).err(),
+               Some("unknown option '--verbose'".to_string()));
+}
+
+#[test]
+fn should_print_show_and_check_documents() {
+    let (dir, build_gradle, properties) = project("check");
+
+    let options = parse_args(&args(&["--format", "json", "show", &build_gradle, &properties])).unwrap();
+    let execution = execute(&options);
+    assert!(execution.success);
+    assert_eq!(execution.document, json!({
+        "command": "show",
+        "files": [
+            {"filename": build_gradle, "version_name": "0.1.0", "version_code": 1},
+            {"filename": properties, "version_name": "0.1.0"},
+        ],
+    }));
+
+    let options = parse_args(&args(&["--format", "json", "--version", "0.2.0", "check", &build_gradle, &properties])).unwrap();
+    let execution = execute(&options);
+    assert!(!execution.success);
+    assert_eq!(execution.document, json!({
+        "command": "check",
+        "files": [
+            {"filename": build_gradle, "version_name": "0.1.0", "version_code": 1, "in_sync": false},
+            {"filename": properties, "version_name": "0.1.0", "in_sync": false},
+        ],
+        "version": "0.2.0",
+    }));
+
+    let options = parse_args(&args(&["--format", "json", "--version", "0.1.0", "check", &build_gradle])).unwrap();
+    let execution = execute(&options);
+    assert!(execution.success);
+    assert_eq!(execution.document["files"][0]["in_sync"], json!(true));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_print_sync_document_with_file_errors() {
+    let (dir, build_gradle, _properties) = project("sync");
+    let missing = dir.join("missing.gradle").to_str().unwrap().to_string();
+
+    let options = parse_args(&args(&["--format", "json", "--version", "0.2.0", "sync", &build_gradle, &missing])).unwrap();
+    let execution = execute(&options);
+    assert!(!execution.success);
+    assert_eq!(execution.written, vec!(build_gradle.clone()));
+    assert_eq!(execution.document, json!({
+        "command": "sync",
+        "files": [
+            {
+                "filename": build_gradle,
+                "old_version_name": "0.1.0",
+                "new_version_name": "0.2.0",
+                "old_version_code": 1,
+                "new_version_code": 2,
+                "written": true,
+                "changed_lines": [
+                    {"line": 1, "old": "versionCode 1", "new": "versionCode 2"},
+                    {"line": 2, "old": "versionName \"0.1.0\"", "new": "versionName \"0.2.0\""},
+                ],
+                "warnings": [],
+            },
+            {"filename": missing, "error": format!("failed to read file: {}", missing)},
+        ],
+        "version": "0.2.0",
+    }));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_print_error_document_when_the_version_is_invalid() {
+    let (dir, build_gradle, _properties) = project("invalid");
+    let options = parse_args(&args(&["--format", "json", "--version", "1.x", "sync", &build_gradle])).unwrap();
+    let execution = execute(&options);
+    assert!(!execution.success);
+    assert!(execution.written.is_empty());
+    assert_eq!(execution.document, json!({
+        "command": "sync",
+        "error": "failed to parse version string '1.x'",
+    }));
+    assert_eq!(fs::read_to_string(&build_gradle).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_exit_with_the_status_of_the_command() {
+    let (dir, build_gradle, _properties) = project("exit");
+    assert_eq!(run_cli(&args(&[])), 2);
+    assert_eq!(run_cli(&args(&["--policy", "never", "check", &build_gradle])), 2);
+    assert_eq!(run_cli(&args(&["release", &build_gradle])), 2);
+    assert_eq!(run_cli(&args(&["--version", "0.2.0", "check", &build_gradle])), 1);
+    assert_eq!(run_cli(&args(&["--version", "0.2.0", "sync", &build_gradle])), 0);
+    assert_eq!(run_cli(&args(&["--version", "0.2.0", "check", &build_gradle])), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_only_acknowledge_the_files_a_sync_wrote() {
+    let (dir, build_gradle, properties) = project("watch");
+    let mut watcher = Watcher::new(&[&build_gradle, &properties], Duration::from_millis(20));
+
+    let options = parse_args(&args(&["--version", "0.1.0", "sync", &build_gradle, &properties])).unwrap();
+    let execution = execute(&options);
+    assert!(execution.written.is_empty());
+    // An edit made while syncing is still reported.
+    fs::write(&properties, "projectVersion=0.2.0\n").unwrap();
+    watcher.acknowledge(&execution.written);
+    assert!(!watcher.poll());
+    thread::sleep(Duration::from_millis(30));
+    assert!(watcher.poll());
+
+    let options = parse_args(&args(&["--version", "0.2.0", "sync", &build_gradle, &properties])).unwrap();
+    let execution = execute(&options);
+    assert_eq!(execution.written, vec!(build_gradle.clone()));
+    watcher.acknowledge(&execution.written);
+    thread::sleep(Duration::from_millis(30));
+    assert!(!watcher.poll());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,55 @@
+use semver::Version;
+
+use dependency::DependencyContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_string_and_map_notation() {
+    let file_content = "dependencies {
+    implementation \"com.example:rustcore-extra:9.9.9\"
+    implementation 'com.example:rustcore:1.2.3@aar'
+}".as_bytes();
+    let content = DependencyContent::parse(file_content, "com.example:rustcore").unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.2.3");
+
+    let file_content = "dependencies {
+    implementation(group = \"com.example\", name = \"rustcore\", version = \"1.2.3\")
+}".as_bytes();
+    let content = DependencyContent::parse(file_content, "com.example:rustcore").unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.2.3");
+
+    let file_content = "// implementation 'com.example:rustcore:1.2.3'".as_bytes();
+    let content = DependencyContent::parse(file_content, "com.example:rustcore");
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find dependency com.example:rustcore".to_string()));
+
+    let file_content = "implementation \"com.example:rustcore:$rustVersion\"".as_bytes();
+    let content = DependencyContent::parse(file_content, "com.example:rustcore");
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "version of com.example:rustcore on line 1 is interpolated ($rustVersion), \
+             replace it with a literal version such as \"1.0.0\" to synchronize it".to_string()));
+}
+
+#[test]
+fn should_write_every_occurrence() {
+    let file_content = "dependencies {
+    implementation \"com.example:rustcore:0.9.0\"
+    testImplementation group: 'com.example',
+        name: 'rustcore', version: '0.9.0', classifier: 'tests'
+    implementation 'com.example:other:0.9.0'
+}
+".as_bytes();
+    let expected_file_content = "dependencies {
+    implementation \"com.example:rustcore:1.0.0\"
+    testImplementation group: 'com.example',
+        name: 'rustcore', version: '1.0.0', classifier: 'tests'
+    implementation 'com.example:other:0.9.0'
+}
+";
+
+    let mut content = DependencyContent::parse(file_content, "com.example:rustcore").unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), expected_file_content);
+}
@@ -0,0 +1,167 @@
+use semver::Version;
+use std::io::Read;
+use std::path::Path;
+
+use buildgradle::BuildGradleContent;
+use configfile::{ConfigurationFormat, GradleFile};
+use detect::{AnyGradleFile, FormatKind, FormatProvider, FormatRegistry, sync_str};
+use error::{Error, GradleResult};
+use version::{GradleVersion, SyncAction, SyncOptions, sem_version_parse};
+
+#[test]
+fn should_detect_format_from_filename() {
+    let cases = [
+        ("app/build.gradle", FormatKind::BuildGradle),
+        ("app/build.gradle.kts", FormatKind::BuildGradleKts),
+        ("gradle.properties", FormatKind::Properties),
+        ("gradle/libs.versions.toml", FormatKind::VersionCatalog),
+        ("app/src/main/AndroidManifest.xml", FormatKind::AndroidManifest),
+        ("ios/App/Info.plist", FormatKind::InfoPlist),
+        ("ios/App.xcodeproj/project.pbxproj", FormatKind::XcodeProject),
+        ("flutter/pubspec.yaml", FormatKind::Pubspec),
+        ("js/package.json", FormatKind::PackageJson),
+        ("js/package-lock.json", FormatKind::PackageJson),
+        ("jvm/pom.xml", FormatKind::Pom),
+    ];
+    cases.iter().for_each(|&(filename, kind)| {
+        assert_eq!(FormatKind::from_filename(filename), Some(kind));
+    });
+    assert_eq!(FormatKind::from_filename("version.json"), None);
+    assert_eq!(FormatKind::from_filename("Cargo.toml"), None);
+}
+
+#[test]
+fn should_detect_format_from_content() {
+    assert_eq!(
+        FormatKind::detect("version.txt", "versionCode 2\nversionName \"1.0.0\""),
+        Ok(FormatKind::BuildGradle));
+    assert_eq!(
+        FormatKind::detect("version.txt", "versionCode = 2\nversionName = \"1.0.0\""),
+        Ok(FormatKind::BuildGradleKts));
+    assert_eq!(
+        FormatKind::detect("manifest.xml", "<manifest android:versionCode=\"1\">"),
+        Ok(FormatKind::AndroidManifest));
+    assert_eq!(
+        FormatKind::detect("jvm/build.gradle", "group = 'com.example'\nversion = '1.2.3'"),
+        Ok(FormatKind::GradleProject));
+    assert_eq!(
+        FormatKind::detect("jvm/build.gradle.kts", "version = \"1.2.3\""),
+        Ok(FormatKind::GradleProject));
+    assert_eq!(
+        FormatKind::detect("version.txt", "hello world"),
+        Err(Error::UnsupportedFormat(
+            "unable to detect the format of 'version.txt'".to_string())));
+}
+
+#[test]
+fn should_sync_and_check_any_file() {
+    let mut file_content = "projectVersion=1.2.0\n".as_bytes();
+    let old_version = Version::parse("1.2.0").unwrap();
+    let new_version = Version::parse("1.3.0").unwrap();
+
+    let content = FormatKind::Properties.parse(&mut file_content).unwrap();
+    let mut file = AnyGradleFile::with_content("gradle.properties", content);
+    assert_eq!(file.filename(), "gradle.properties");
+    assert!(file.check_version(&old_version).is_ok());
+    assert!(file.check_version(&new_version).is_err());
+
+    file.sync_version(&new_version).unwrap();
+    assert!(file.is_modified());
+    assert!(file.check_version(&new_version).is_ok());
+}
+
+struct VersionJson {
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for VersionJson {
+    fn from<R: Read>(mut reader: R) -> GradleResult<Self> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let name = content.trim().trim_start_matches("{\"version\":\"").trim_end_matches("\"}");
+        Ok(VersionJson {
+            version: GradleVersion::new(1, sem_version_parse(name)?),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        self.modified |= action.is_modified();
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        format!("{{\"version\":\"{}\"}}", self.version.version())
+    }
+}
+
+struct VersionJsonProvider;
+
+impl FormatProvider for VersionJsonProvider {
+    fn name(&self) -> &str {
+        "version.json"
+    }
+    fn matches_filename(&self, filename: &str) -> bool {
+        filename.ends_with("version.json")
+    }
+    fn parse(&self, reader: &mut dyn Read) -> GradleResult<Box<dyn ConfigurationFormat>> {
+        Ok(Box::new(<VersionJson as ConfigurationFormat>::from(reader)?))
+    }
+}
+
+#[test]
+fn should_use_registered_third_party_format() {
+    let mut registry = FormatRegistry::new();
+    assert!(registry.detect("app/version.json", "").is_err());
+
+    registry.register(Box::new(VersionJsonProvider));
+    let provider = registry.detect("app/version.json", "").unwrap();
+    assert_eq!(provider.name(), "version.json");
+    assert_eq!(registry.detect("build.gradle", "").unwrap().name(), "build.gradle");
+
+    let mut file_content = "{\"version\":\"0.1.0\"}".as_bytes();
+    let content = provider.parse(&mut file_content).unwrap();
+    let mut file = AnyGradleFile::with_content("app/version.json", content);
+    file.sync_version(&Version::parse("0.2.0").unwrap()).unwrap();
+
+    assert!(file.is_modified());
+    assert_eq!(file.current_version().unwrap().version().to_string(), "0.2.0");
+}
+
+#[test]
+fn should_report_missing_file() {
+    let file = AnyGradleFile::new("does/not/exist/build.gradle");
+    assert_eq!(file.err().unwrap(), Error::IoError(
+            "failed to read file: does/not/exist/build.gradle".to_string()));
+}
+
+#[test]
+fn should_sync_string_in_memory() {
+    let content = "version: 1.2.0+7\nname: app\n";
+    let (new_content, report) = sync_str(&FormatKind::Pubspec, content, &Version::parse("1.3.0").unwrap()).unwrap();
+    assert_eq!(new_content, "version: 1.3.0+8\nname: app\n");
+    assert_eq!(report.filename, "pubspec.yaml");
+    assert_eq!(report.new_version_code, Some(8));
+    assert!(!report.written);
+
+    let result = sync_str(&FormatKind::Pubspec, content, &Version::parse("1.1.0").unwrap());
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_read_file_from_any_reader() {
+    let file_content = "versionCode 2\nversionName \"1.0.0\"\n";
+    let mut file = GradleFile::<BuildGradleContent>::from_reader("does/not/exist/build.gradle", file_content.as_bytes()).unwrap();
+    file.sync_version(&Version::parse("1.1.0").unwrap()).unwrap();
+    assert_eq!(file.render(), "versionCode 3\nversionName \"1.1.0\"\n");
+
+    let file = AnyGradleFile::from_reader("does/not/exist/gradle.properties", "projectVersion=0.4.0".as_bytes()).unwrap();
+    assert_eq!(file.current_version().unwrap().version().to_string(), "0.4.0");
+    assert!(!Path::new("does/not/exist/gradle.properties.lock").exists());
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use semver::Version;
+
+use buildgradle::BuildGradleContent;
+use configfile::GradleFile;
+use fastlane::ReleaseNotes;
+use properties::PropertiesContent;
+use version::GradleVersion;
+
+fn metadata_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("gradle-sync-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for locale in &["en-US", "de-DE", "fr-FR"] {
+        fs::create_dir_all(dir.join("metadata").join(locale).join("changelogs")).unwrap();
+    }
+    fs::write(dir.join("metadata/fr-FR/changelogs/8.txt"), "Corrections\n").unwrap();
+    dir
+}
+
+#[test]
+fn should_create_release_notes_and_report_missing_locales() {
+    let dir = metadata_dir("notes");
+    let release_notes = ReleaseNotes::new(dir.join("metadata"))
+        .locale("en-US")
+        .with_template("Version {versionName} ({versionCode})");
+    let version = GradleVersion::new(8, Version::parse("1.2.0").unwrap());
+
+    let report = release_notes.update(&version).unwrap();
+    assert_eq!(report.created, vec!(dir.join("metadata/en-US/changelogs/8.txt")));
+    assert_eq!(report.missing_locales, vec!("de-DE".to_string()));
+    assert_eq!(fs::read_to_string(dir.join("metadata/en-US/changelogs/8.txt")).unwrap(),
+        "Version 1.2.0 (8)\n");
+
+    fs::write(dir.join("metadata/en-US/changelogs/8.txt"), "Edited\n").unwrap();
+    let report = release_notes.update(&version).unwrap();
+    assert!(report.created.is_empty());
+    assert_eq!(fs::read_to_string(dir.join("metadata/en-US/changelogs/8.txt")).unwrap(), "Edited\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_create_release_notes_from_changelog_on_sync() {
+    let dir = metadata_dir("sync");
+    fs::write(dir.join("CHANGELOG.md"), "## [1.2.0] - 2024-03-04\n- Faster sync\n\n## [1.1.0]\n- Fix\n").unwrap();
+    fs::write(dir.join("build.gradle"), "versionCode 7\nversionName \"1.1.0\"\n").unwrap();
+    let release_notes = ReleaseNotes::new(dir.join("metadata"))
+        .locale("en-US")
+        .locale("de-DE")
+        .with_changelog(dir.join("CHANGELOG.md").to_str().unwrap());
+
+    let mut file = GradleFile::<BuildGradleContent>::new(dir.join("build.gradle").to_str().unwrap()).unwrap()
+        .with_release_notes(release_notes);
+    let report = file.sync_and_write(&Version::parse("1.2.0").unwrap()).unwrap();
+    assert!(report.warnings.is_empty());
+    assert_eq!(fs::read_to_string(dir.join("metadata/de-DE/changelogs/8.txt")).unwrap(), "- Faster sync\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_report_configured_locale_without_notes() {
+    let dir = metadata_dir("missing");
+    fs::write(dir.join("CHANGELOG.md"), "## [1.1.0]\n- Fix\n").unwrap();
+    let release_notes = ReleaseNotes::new(dir.join("metadata"))
+        .locale("ja-JP")
+        .with_changelog(dir.join("CHANGELOG.md").to_str().unwrap());
+    let version = GradleVersion::new(8, Version::parse("1.2.0").unwrap());
+
+    let report = release_notes.update(&version).unwrap();
+    assert!(report.created.is_empty());
+    assert_eq!(report.missing_locales, vec!("de-DE".to_string(), "en-US".to_string(), "ja-JP".to_string()));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_not_create_release_notes_without_version_code() {
+    let dir = metadata_dir("properties");
+    fs::write(dir.join("gradle.properties"), "projectVersion=1.1.0\n").unwrap();
+    let release_notes = ReleaseNotes::new(dir.join("metadata"))
+        .locale("en-US")
+        .with_template("Version {versionName}");
+
+    let mut file = GradleFile::<PropertiesContent>::new(dir.join("gradle.properties").to_str().unwrap()).unwrap()
+        .with_release_notes(release_notes);
+    let report = file.sync_and_write(&Version::parse("1.2.0").unwrap()).unwrap();
+    assert!(report.warnings.is_empty());
+    assert!(!dir.join("metadata/en-US/changelogs/2.txt").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+use semver::Version;
+
+use gradleproject::GradleProjectContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_top_level_project_version() {
+    let file_content = "
+    group = 'com.example'
+    version = '1.2.3'
+
+    dependencies {
+        implementation('com.example:core') {
+            version { strictly '0.1.0' }
+        }
+    }".as_bytes();
+    let content = <GradleProjectContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.2.3");
+
+    let file_content = "dependencies {\n    version = '0.1.0'\n}".as_bytes();
+    let content = <GradleProjectContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find project version".to_string()));
+
+    let file_content = "version = \"${major}.0.0\"".as_bytes();
+    let content = <GradleProjectContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "project version on line 1 is interpolated (\"${major}.0.0\"), \
+             replace it with a literal version such as \"1.0.0\" to synchronize it".to_string()));
+
+    let file_content = "version = 'é\n".as_bytes();
+    let content = <GradleProjectContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::ParsingFailed(
+            "project version on line 1 is an unterminated string".to_string()));
+}
+
+#[test]
+fn should_write_new_project_version() {
+    let file_content = "plugins {
+    id 'java-library'
+}
+
+allprojects {
+    version = '0.9.0' // released by CI
+}
+".as_bytes();
+    let expected_file_content = "plugins {
+    id 'java-library'
+}
+
+allprojects {
+    version = '1.0.0' // released by CI
+}
+";
+
+    let mut content = <GradleProjectContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), expected_file_content);
+}
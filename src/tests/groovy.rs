@@ -0,0 +1,76 @@
+use groovy::{tokenize, find_statements, primary_statement, Quote, TokenKind};
+
+#[test]
+fn should_skip_comments() {
+    let source = "// versionCode 1\n/* versionCode 2\n versionCode 3 */ versionCode 4";
+    let tokens = tokenize(source);
+    let statements = find_statements(source, &tokens, "versionCode");
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].value.text(source), "4");
+}
+
+#[test]
+fn should_tokenize_strings() {
+    let source = r#"println "versionCode 3" 'a\'b' """x"y""" "${major}.0""#;
+    let kinds: Vec<TokenKind> = tokenize(source).iter().map(|token| token.kind).collect();
+    assert_eq!(kinds, vec!(
+        TokenKind::Identifier,
+        TokenKind::String { quote: Quote::Double, interpolated: false, terminated: true },
+        TokenKind::String { quote: Quote::Single, interpolated: false, terminated: true },
+        TokenKind::String { quote: Quote::TripleDouble, interpolated: false, terminated: true },
+        TokenKind::String { quote: Quote::Double, interpolated: true, terminated: true },
+    ));
+    let tokens = tokenize(source);
+    assert_eq!(&source[tokens[1].content()], "versionCode 3");
+}
+
+#[test]
+fn should_only_find_statements() {
+    let source = "
+    def versionCode = 3
+    println versionCode 4
+    versionCode 5 + offset
+    android { defaultConfig { versionCode 6 }; versionCode 7 }";
+    let tokens = tokenize(source);
+    let values: Vec<&str> = find_statements(source, &tokens, "versionCode").iter()
+        .map(|statement| statement.value.text(source))
+        .collect();
+    assert_eq!(values, vec!("6", "7"));
+}
+
+#[test]
+fn should_prefer_default_config_block() {
+    let source = "
+    android {
+        productFlavors { free { versionCode 9 } }
+        defaultConfig {
+            versionCode 2
+        }
+    }";
+    let tokens = tokenize(source);
+    let statements = find_statements(source, &tokens, "versionCode");
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0].blocks, vec!("android", "productFlavors", "free"));
+    let primary = primary_statement(&statements).unwrap();
+    assert_eq!(primary.value.text(source), "2");
+}
+
+#[test]
+fn should_tokenize_non_ascii_strings() {
+    let source = "def s = 'a\\é' + \"ü${x}\"\nversionCode 4";
+    let kinds: Vec<TokenKind> = tokenize(source).iter().map(|token| token.kind).collect();
+    assert_eq!(kinds[3], TokenKind::String { quote: Quote::Single, interpolated: false, terminated: true });
+    assert_eq!(kinds[5], TokenKind::String { quote: Quote::Double, interpolated: true, terminated: true });
+    let tokens = tokenize(source);
+    assert_eq!(find_statements(source, &tokens, "versionCode")[0].value.text(source), "4");
+}
+
+#[test]
+fn should_not_strip_a_missing_closing_quote() {
+    let source = "versionName \"1é\nversionCode 4";
+    let tokens = tokenize(source);
+    let statement = &find_statements(source, &tokens, "versionName")[0];
+    assert!(statement.value.is_unterminated());
+    assert_eq!(&source[statement.value.content()], "1é");
+    assert_eq!(find_statements(source, &tokens, "versionCode")[0].value.text(source), "4");
+}
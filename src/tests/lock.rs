@@ -0,0 +1,65 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use configfile::GradleFile;
+use error::Error;
+use lock::FileLock;
+use properties::PropertiesContent;
+
+#[test]
+fn should_time_out_while_locked() {
+    let path = env::temp_dir().join(format!("gradle-sync-lock-{}.properties", process::id()));
+    fs::write(&path, "projectVersion=0.1.0\n").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let file = GradleFile::<PropertiesContent>::new(filename).unwrap();
+    assert!(FileLock::lock_path(filename).exists());
+    let result = GradleFile::<PropertiesContent>::open(filename, Duration::from_millis(50));
+    assert_eq!(result.err().unwrap(), Error::LockTimeout(format!(
+        "timed out after 50ms waiting for the lock '{}.lock' held by another sync", filename)));
+
+    drop(file);
+    assert!(!FileLock::lock_path(filename).exists());
+    assert!(GradleFile::<PropertiesContent>::open(filename, Duration::from_millis(50)).is_ok());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn should_take_over_lock_file_left_by_killed_sync() {
+    let path = env::temp_dir().join(format!("gradle-sync-stale-{}.properties", process::id()));
+    fs::write(&path, "projectVersion=0.1.0\n").unwrap();
+    let filename = path.to_str().unwrap();
+    fs::write(FileLock::lock_path(filename), "999999").unwrap();
+
+    let file = GradleFile::<PropertiesContent>::open(filename, Duration::from_millis(50)).unwrap();
+    assert_eq!(fs::read_to_string(FileLock::lock_path(filename)).unwrap(), process::id().to_string());
+    drop(file);
+    assert!(!FileLock::lock_path(filename).exists());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn should_serialize_parallel_syncs() {
+    let path = env::temp_dir().join(format!("gradle-sync-parallel-{}.gradle", process::id()));
+    fs::write(&path, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    let filename = path.to_str().unwrap().to_string();
+
+    let threads: Vec<_> = (0..4).map(|_| {
+        let filename = filename.clone();
+        thread::spawn(move || {
+            let mut file = ::AnyGradleFile::new(&filename).unwrap();
+            file.sync_and_write(&"0.2.0".parse().unwrap()).unwrap()
+        })
+    }).collect();
+    let written = threads.into_iter()
+        .map(|thread| thread.join().unwrap())
+        .filter(|report| report.written)
+        .count();
+
+    assert_eq!(written, 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "versionCode 2\nversionName \"0.2.0\"\n");
+    fs::remove_file(&path).unwrap();
+}
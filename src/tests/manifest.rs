@@ -0,0 +1,56 @@
+use semver::Version;
+
+use manifest::AndroidManifestContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_manifest_attributes() {
+    let file_content = r#"<manifest android:versionCode="12" android:versionName="1.2.3">"#.as_bytes();
+    let content = <AndroidManifestContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 12);
+    assert_eq!(version.version().to_string(), "1.2.3");
+
+    let file_content = "versionCode 12\nversionName \"1.2.3\"".as_bytes();
+    let content = <AndroidManifestContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find android:versionCode".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example"
+    android:versionCode="7"
+    android:versionName="0.9.0">
+</manifest>"#.as_bytes();
+    let expected_file_content = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example"
+    android:versionCode="8"
+    android:versionName="1.0.0">
+</manifest>"#;
+
+    let mut content = <AndroidManifestContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_read_back_pre_release_version_name() {
+    let file_content = r#"<manifest android:versionCode="7" android:versionName="1.0.0">"#.as_bytes();
+
+    let mut content = <AndroidManifestContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.1.0-beta.1").unwrap())
+        .expect("failed to synchronize version");
+    let rendered = content.render();
+    assert_eq!(rendered, r#"<manifest android:versionCode="8" android:versionName="1.1.0-beta.1">"#);
+    let content = <AndroidManifestContent as ConfigurationFormat>::from(rendered.as_bytes()).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.1.0-beta.1");
+}
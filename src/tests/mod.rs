@@ -1,3 +1,25 @@
 mod version;
 mod buildgradle;
-mod properties;
\ No newline at end of file
+mod buildgradlekts;
+mod properties;
+mod versioncatalog;
+mod manifest;
+mod detect;
+mod report;
+mod cargo;
+mod groovy;
+mod roundtrip;
+mod plist;
+mod pbxproj;
+mod pubspec;
+mod packagejson;
+mod gradleproject;
+mod pom;
+mod dependency;
+mod changelog;
+mod fastlane;
+mod watch;
+mod lock;
+mod state;
+mod backup;
+mod session;
\ No newline at end of file
@@ -0,0 +1,87 @@
+use semver::Version;
+
+use packagejson::PackageJsonContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_top_level_version() {
+    let file_content = r#"{
+  "name": "react-native-core",
+  "dependencies": {"react": {"version": "18.2.0"}},
+  "version": "1.2.3"
+}"#.as_bytes();
+    let content = <PackageJsonContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.version().to_string(), "1.2.3");
+
+    let file_content = r#"{"name": "core", "config": {"version": "1.2.3"}}"#.as_bytes();
+    let content = <PackageJsonContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find top-level \"version\" in package.json".to_string()));
+}
+
+#[test]
+fn should_write_new_version_without_reformatting() {
+    let file_content = r#"{
+    "name" : "react-native-core",
+    "version" : "0.9.0",
+    "scripts": { "build": "echo \"version\": \"0.9.0\"" },
+    "peerDependencies": { "react-native": "0.9.0" }
+}
+"#.as_bytes();
+    let expected_file_content = r#"{
+    "name" : "react-native-core",
+    "version" : "1.0.0",
+    "scripts": { "build": "echo \"version\": \"0.9.0\"" },
+    "peerDependencies": { "react-native": "0.9.0" }
+}
+"#;
+
+    let mut content = <PackageJsonContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), expected_file_content);
+}
+
+#[test]
+fn should_write_root_package_of_lockfile() {
+    let file_content = r#"{
+  "name": "core",
+  "version": "0.9.0",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "core",
+      "version": "0.9.0"
+    },
+    "node_modules/left-pad": {
+      "version": "0.9.0"
+    }
+  }
+}"#.as_bytes();
+    let expected_file_content = r#"{
+  "name": "core",
+  "version": "1.0.0",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "core",
+      "version": "1.0.0"
+    },
+    "node_modules/left-pad": {
+      "version": "0.9.0"
+    }
+  }
+}"#;
+
+    let mut content = <PackageJsonContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), expected_file_content);
+
+    let mut content = <PackageJsonContent as ConfigurationFormat>::from(expected_file_content.as_bytes()).unwrap();
+    let result = content.sync_version(&Version::parse("0.9.0").unwrap());
+    assert_eq!(result, Err(Error::VersionNotIncreasing(
+            "version not increasing (old)1.0.0 > (new)0.9.0".to_string())));
+}
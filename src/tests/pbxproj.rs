@@ -0,0 +1,83 @@
+use semver::Version;
+
+use pbxproj::XcodeProjectContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_build_settings() {
+    let file_content = "buildSettings = {
+				CURRENT_PROJECT_VERSION = 45;
+				MARKETING_VERSION = \"1.2.3\";
+			};".as_bytes();
+    let content = <XcodeProjectContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 45);
+    assert_eq!(version.version().to_string(), "1.2.3");
+
+    let file_content = "buildSettings = {
+				CURRENT_PROJECT_VERSION = 45;
+			};".as_bytes();
+    let content = <XcodeProjectContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find MARKETING_VERSION".to_string()));
+}
+
+#[test]
+fn should_write_every_build_configuration() {
+    let file_content = "/* Debug */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 7;
+				INFOPLIST_FILE = App/Info.plist;
+				MARKETING_VERSION = 0.9.0;
+			};
+		};
+		/* Release */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 7;
+				INFOPLIST_FILE = App/Info.plist;
+				MARKETING_VERSION = 0.9.0;
+			};
+		};".as_bytes();
+    let expected_file_content = "/* Debug */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 8;
+				INFOPLIST_FILE = App/Info.plist;
+				MARKETING_VERSION = 1.0.0;
+			};
+		};
+		/* Release */ = {
+			buildSettings = {
+				CURRENT_PROJECT_VERSION = 8;
+				INFOPLIST_FILE = App/Info.plist;
+				MARKETING_VERSION = 1.0.0;
+			};
+		};";
+
+    let mut content = <XcodeProjectContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_keep_xcode_default_two_component_version() {
+    let file_content = "CURRENT_PROJECT_VERSION = 1;\nMARKETING_VERSION = 1.0;\n".as_bytes();
+
+    let mut content = <XcodeProjectContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.0.0");
+    content.sync_version(&Version::parse("1.1.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), "CURRENT_PROJECT_VERSION = 2;\nMARKETING_VERSION = 1.1;\n");
+    content.sync_version(&Version::parse("1.1.1").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), "CURRENT_PROJECT_VERSION = 3;\nMARKETING_VERSION = 1.1.1;\n");
+
+    let file_content = "CURRENT_PROJECT_VERSION = 1;\nMARKETING_VERSION = 1;\n".as_bytes();
+    let content = <XcodeProjectContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "found MARKETING_VERSION 1, not a semantic version".to_string()));
+}
@@ -0,0 +1,97 @@
+use semver::Version;
+
+use plist::InfoPlistContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_bundle_versions() {
+    let file_content = "<dict>
+	<key>CFBundleShortVersionString</key>
+	<string>1.2.3</string>
+	<key>CFBundleVersion</key>
+	<string>45</string>
+</dict>".as_bytes();
+    let content = <InfoPlistContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 45);
+    assert_eq!(version.version().to_string(), "1.2.3");
+}
+
+#[test]
+fn fail_on_build_setting_reference() {
+    let file_content = "<dict>
+	<key>CFBundleShortVersionString</key>
+	<string>$(MARKETING_VERSION)</string>
+	<key>CFBundleVersion</key>
+	<string>45</string>
+</dict>".as_bytes();
+    let content = <InfoPlistContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "CFBundleShortVersionString refers to the build setting $(MARKETING_VERSION), \
+             synchronize project.pbxproj instead".to_string()));
+}
+
+#[test]
+fn fail_on_non_integer_bundle_version() {
+    let file_content = "<dict>
+	<key>CFBundleShortVersionString</key>
+	<string>1.2.3</string>
+	<key>CFBundleVersion</key>
+	<string>1.2.3</string>
+</dict>".as_bytes();
+    let content = <InfoPlistContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "found CFBundleVersion 1.2.3, not an integer versionCode".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleName</key>
+	<string>App</string>
+	<key>CFBundleShortVersionString</key>
+	<string>0.9.0</string>
+	<key>CFBundleVersion</key>
+	<string>7</string>
+</dict>
+</plist>
+"#.as_bytes();
+    let expected_file_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleName</key>
+	<string>App</string>
+	<key>CFBundleShortVersionString</key>
+	<string>1.0.0</string>
+	<key>CFBundleVersion</key>
+	<string>8</string>
+</dict>
+</plist>
+"#;
+
+    let mut content = <InfoPlistContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_keep_xcode_default_two_component_version() {
+    let file_content = "<key>CFBundleShortVersionString</key>\n<string>1.0</string>\n\
+                        <key>CFBundleVersion</key>\n<string>1</string>".as_bytes();
+
+    let mut content = <InfoPlistContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.0.0");
+    content.sync_version(&Version::parse("2.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), "<key>CFBundleShortVersionString</key>\n<string>2.0</string>\n\
+                                  <key>CFBundleVersion</key>\n<string>2</string>");
+}
@@ -0,0 +1,77 @@
+use semver::Version;
+
+use pom::PomContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_project_version_only() {
+    let file_content = "<project>
+  <parent><version>5.0.0</version></parent>
+  <!-- <version>0.0.1</version> -->
+  <version>1.2.3</version>
+</project>".as_bytes();
+    let content = <PomContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.2.3");
+
+    let file_content = "<project><parent><version>5.0.0</version></parent></project>".as_bytes();
+    let content = <PomContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find <project><version>".to_string()));
+
+    let file_content = "<project><version>${revision}</version></project>".as_bytes();
+    let content = <PomContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::UnsupportedSyntax(
+            "<project><version> refers to the property ${revision}, \
+             replace it with a literal version to synchronize it".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>0.9.0</version>
+  </parent>
+  <artifactId>core</artifactId>
+  <version> 0.9.0 </version>
+  <dependencies>
+    <dependency>
+      <groupId>com.example</groupId>
+      <artifactId>util</artifactId>
+      <version>0.9.0</version>
+    </dependency>
+  </dependencies>
+</project>
+"#.as_bytes();
+    let expected_file_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>0.9.0</version>
+  </parent>
+  <artifactId>core</artifactId>
+  <version> 1.0.0 </version>
+  <dependencies>
+    <dependency>
+      <groupId>com.example</groupId>
+      <artifactId>util</artifactId>
+      <version>0.9.0</version>
+    </dependency>
+  </dependencies>
+</project>
+"#;
+
+    let mut content = <PomContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
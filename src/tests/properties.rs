@@ -5,6 +5,7 @@ use properties::replace_project_version_from_line;
 use properties::PropertiesContent;
 
 use configfile::ConfigurationFormat;
+use version::VersionPolicy;
 
 #[test]
 fn should_parse_project_version_from_line() {
@@ -84,7 +85,7 @@ fn should_write_new_version_to_writer() {
     let new_version = Version::parse("1.3.4").unwrap();
     let expected_file_content = "
     projectVersion=1.3.4
-    \n";
+    ";
 
     let mut content = <PropertiesContent as ConfigurationFormat>::from(file_content).unwrap();
     content.sync_version(&new_version)
@@ -94,3 +95,21 @@ fn should_write_new_version_to_writer() {
         .expect("failed to write content to writer");
     assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
 }
+
+#[test]
+fn should_apply_version_policy_when_decreasing() {
+    let file_content = "projectVersion=1.2.0".as_bytes();
+    let lower_version = Version::parse("1.1.0").unwrap();
+
+    let mut content = <PropertiesContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert!(content.sync_version(&lower_version).is_err());
+    assert!(!content.is_modified());
+
+    let action = content.sync_version_with_policy(&lower_version, VersionPolicy::WarnAndSkip).unwrap();
+    assert!(action.warning().is_some());
+    assert!(!content.is_modified());
+
+    content.sync_version_with_policy(&lower_version, VersionPolicy::AllowDowngrade).unwrap();
+    assert!(content.is_modified());
+    assert_eq!(content.lines(), vec!("projectVersion=1.1.0".to_string()));
+}
@@ -0,0 +1,72 @@
+use semver::Version;
+
+use pubspec::PubspecContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn parse_version_and_build_number() {
+    let file_content = "name: app\nversion: 1.2.3+45\n".as_bytes();
+    let content = <PubspecContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 45);
+    assert_eq!(version.version().to_string(), "1.2.3");
+
+    let file_content = "name: app\nversion: '1.2.3-beta.1'\n".as_bytes();
+    let content = <PubspecContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 1);
+    assert_eq!(version.version().to_string(), "1.2.3-beta.1");
+
+    let file_content = "name: app\ndependencies:\n  http:\n    version: 1.2.3\n".as_bytes();
+    let content = <PubspecContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find version in pubspec".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = "name: app
+description: A Flutter plugin.
+version: \"0.9.0+7\" # bumped by gradle-sync
+
+environment:
+  sdk: '>=3.0.0 <4.0.0'
+".as_bytes();
+    let expected_file_content = "name: app
+description: A Flutter plugin.
+version: \"1.0.0+8\" # bumped by gradle-sync
+
+environment:
+  sdk: '>=3.0.0 <4.0.0'
+";
+
+    let mut content = <PubspecContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_append_missing_build_number() {
+    let file_content = "name: app\nversion: 0.9.0\n".as_bytes();
+
+    let mut content = <PubspecContent as ConfigurationFormat>::from(file_content).unwrap();
+    assert_eq!(content.render(), "name: app\nversion: 0.9.0\n");
+    content.sync_version(&Version::parse("1.0.0").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), "name: app\nversion: 1.0.0+2\n");
+}
+
+#[test]
+fn should_drop_build_metadata_of_new_version() {
+    let file_content = "name: app\nversion: 1.0.0+1\n".as_bytes();
+
+    let mut content = <PubspecContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.1.0+build5").unwrap())
+        .expect("failed to synchronize version");
+    assert_eq!(content.render(), "name: app\nversion: 1.1.0+2\n");
+}
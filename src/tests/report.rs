@@ -0,0 +1,52 @@
+use semver::Version;
+
+use configfile::GradleFile;
+use properties::PropertiesContent;
+use configfile::ConfigurationFormat;
+use report::{ChangedLine, SyncReport};
+use version::{GradleVersion, VersionPolicy};
+
+#[test]
+fn should_report_changed_lines() {
+    let old_version = GradleVersion::new(1, Version::parse("1.0.0").unwrap());
+    let new_version = GradleVersion::new(2, Version::parse("1.1.0").unwrap());
+    let mut report = SyncReport::new("build.gradle", &old_version, &new_version);
+    report.diff_lines(
+        &["android {".to_string(), "versionCode 1".to_string()],
+        &["android {".to_string(), "versionCode 2".to_string()]);
+
+    assert!(report.is_changed());
+    assert_eq!(report.changed_lines, vec!(ChangedLine {
+        line: 2,
+        old: "versionCode 1".to_string(),
+        new: "versionCode 2".to_string(),
+    }));
+    assert_eq!(report.to_string(),
+        "build.gradle: versionName 1.0.0 -> 1.1.0, versionCode 1 -> 2\n  line 2: versionCode 1 -> versionCode 2");
+}
+
+#[test]
+fn should_report_sync_of_gradle_file() {
+    let file_content = "
+    projectVersion=1.2.0
+    ".as_bytes();
+    let content = <PropertiesContent as ConfigurationFormat>::from(file_content).unwrap();
+    let mut file = GradleFile::with_content("gradle.properties", Box::new(content))
+        .with_policy(VersionPolicy::WarnAndSkip);
+
+    let report = file.sync_version(&Version::parse("1.3.0").unwrap()).unwrap();
+    assert_eq!(report.old_version_name, "1.2.0");
+    assert_eq!(report.new_version_name, "1.3.0");
+    assert_eq!(report.new_version_code, None);
+    assert!(!report.written);
+    assert_eq!(report.changed_lines.len(), 1);
+    assert_eq!(report.changed_lines[0].line, 2);
+    assert!(report.warnings.is_empty());
+
+    let report = file.sync_version(&Version::parse("1.0.0").unwrap()).unwrap();
+    assert!(!report.is_changed());
+    assert!(report.changed_lines.is_empty());
+    assert_eq!(report.warnings, vec!("version not increasing (old)1.3.0 > (new)1.0.0".to_string()));
+    assert_eq!(report.to_string(),
+        "gradle.properties: versionName 1.3.0 (unchanged)\n  warning: version not increasing (old)1.3.0 > (new)1.0.0");
+}
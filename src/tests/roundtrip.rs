@@ -0,0 +1,55 @@
+use semver::Version;
+
+use buildgradle::BuildGradleContent;
+use properties::PropertiesContent;
+use manifest::AndroidManifestContent;
+use configfile::ConfigurationFormat;
+
+/// Anything that is neither a version definition nor able to turn the
+/// surrounding text into one: whitespace with mixed line endings, comments
+/// and statements around the definitions.
+const GROOVY_NOISE: &str = "([ \\t]|\\r?\\n|// [a-zA-Z0-9 {}\"'éß]*\\r?\\n|/\\* [a-zA-Z0-9 \\n{}\"'éß]* \\*/|minSdkVersion [0-9]{1,2}\\n)*";
+const PROPERTIES_NOISE: &str = "(\\r?\\n|[# ]?[a-z.]{1,10}=[a-zA-Z0-9 é]*\\r?\\n)*";
+const XML_NOISE: &str = "([ \\t]|\\r?\\n|<!-- [a-z ]* -->|[a-z]{1,8}=\"[a-z0-9 ]*\")*";
+
+fn sync<T: ConfigurationFormat>(source: &str, new_version: &Version) -> String {
+    let mut content = <T as ConfigurationFormat>::from(source.as_bytes()).unwrap();
+    content.sync_version(new_version).unwrap();
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content).unwrap();
+    String::from_utf8(real_content).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn build_gradle_keeps_every_other_byte(
+        head in GROOVY_NOISE, middle in GROOVY_NOISE, tail in GROOVY_NOISE,
+        code in 1u32..1000, quote in "[\"']",
+        major in 0u64..10, minor in 0u64..10, patch in 0u64..10) {
+        let source = format!("{}versionCode {}\n{}versionName {}{}.{}.{}{}\n{}",
+                             head, code, middle, quote, major, minor, patch, quote, tail);
+        let expected = format!("{}versionCode {}\n{}versionName {}{}.{}.{}{}\n{}",
+                               head, code + 1, middle, quote, major + 1, minor, patch, quote, tail);
+        let new_version = Version::new(major + 1, minor, patch);
+        prop_assert_eq!(sync::<BuildGradleContent>(&source, &new_version), expected);
+    }
+
+    #[test]
+    fn properties_keeps_every_other_byte(
+        head in PROPERTIES_NOISE, tail in PROPERTIES_NOISE,
+        separator in " ?= ?", minor in 0u64..100) {
+        let source = format!("{}projectVersion{}1.{}.0\n{}", head, separator, minor, tail);
+        let expected = format!("{}projectVersion{}2.{}.0\n{}", head, separator, minor, tail);
+        prop_assert_eq!(sync::<PropertiesContent>(&source, &Version::new(2, minor, 0)), expected);
+    }
+
+    #[test]
+    fn manifest_keeps_every_other_byte(
+        head in XML_NOISE, middle in XML_NOISE, tail in XML_NOISE, code in 1u32..1000) {
+        let source = format!("<manifest {}android:versionCode=\"{}\"{}android:versionName=\"1.0.0\"{}>",
+                             head, code, middle, tail);
+        let expected = format!("<manifest {}android:versionCode=\"{}\"{}android:versionName=\"1.1.0\"{}>",
+                               head, code + 1, middle, tail);
+        prop_assert_eq!(sync::<AndroidManifestContent>(&source, &Version::new(1, 1, 0)), expected);
+    }
+}
@@ -0,0 +1,112 @@
+use std::env;
+use std::fs;
+use std::process;
+use semver::Version;
+
+use configfile::GradleFile;
+use error::Error;
+use properties::PropertiesContent;
+use session::{ConsistencyPolicy, SyncSession};
+
+#[test]
+fn should_write_nothing_when_any_file_fails() {
+    let dir = env::temp_dir().join(format!("gradle-sync-session-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let build_gradle = dir.join("build.gradle");
+    let properties = dir.join("gradle.properties");
+    let missing = dir.join("missing.gradle");
+    let (build_gradle, properties, missing) =
+        (build_gradle.to_str().unwrap(), properties.to_str().unwrap(), missing.to_str().unwrap());
+    fs::write(build_gradle, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(properties, "projectVersion=0.3.0\n").unwrap();
+
+    let mut session = SyncSession::new()
+        .open(build_gradle)
+        .with_file(GradleFile::<PropertiesContent>::new(properties).unwrap())
+        .open(missing);
+    let result = session.sync(&Version::parse("0.2.0").unwrap());
+    assert_eq!(result.err().unwrap(), Error::SyncFailed(format!(
+        "2 of 3 files failed to sync, no file was written:\
+         \n  {}: failed to read file: {}\
+         \n  {}: version not increasing (old)0.3.0 > (new)0.2.0", missing, missing, properties)));
+    assert_eq!(session.failures().len(), 2);
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    drop(session);
+
+    let mut session = SyncSession::new()
+        .open(build_gradle)
+        .open(properties);
+    let reports = session.sync(&Version::parse("0.4.0").unwrap()).unwrap();
+    assert!(reports.iter().all(|report| report.written));
+    assert!(session.failures().is_empty());
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 2\nversionName \"0.4.0\"\n");
+    assert_eq!(fs::read_to_string(properties).unwrap(), "projectVersion=0.4.0\n");
+    drop(session);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_reconcile_or_reject_divergent_versions() {
+    let dir = env::temp_dir().join(format!("gradle-sync-consistency-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let app = dir.join("app.gradle");
+    let wear = dir.join("wear.gradle");
+    let properties = dir.join("gradle.properties");
+    let (app, wear, properties) =
+        (app.to_str().unwrap(), wear.to_str().unwrap(), properties.to_str().unwrap());
+    fs::write(app, "versionCode 12\nversionName \"1.2.0\"\n").unwrap();
+    fs::write(wear, "versionCode 15\nversionName \"1.1.0\"\n").unwrap();
+    fs::write(properties, "projectVersion=1.2.0\n").unwrap();
+
+    let mut session = SyncSession::new()
+        .open(app)
+        .open(wear)
+        .open(properties)
+        .with_consistency(ConsistencyPolicy::Error);
+    assert_eq!(session.check_consistency().err().unwrap(), Error::VersionConflict(format!(
+        "files disagree on the version: {} 1.2.0 (versionCode 12), {} 1.1.0 (versionCode 15), {} 1.2.0",
+        app, wear, properties)));
+    assert!(session.sync(&Version::parse("1.3.0").unwrap()).is_err());
+    assert_eq!(fs::read_to_string(wear).unwrap(), "versionCode 15\nversionName \"1.1.0\"\n");
+    drop(session);
+
+    let mut session = SyncSession::new()
+        .open(app)
+        .open(wear)
+        .open(properties)
+        .with_consistency(ConsistencyPolicy::HighestWins);
+    let reports = session.sync(&Version::parse("1.3.0").unwrap()).unwrap();
+    assert_eq!(reports[0].warnings.len(), 1);
+    assert_eq!(reports[1].warnings.len(), 1);
+    assert!(reports[2].warnings.is_empty());
+    assert_eq!(fs::read_to_string(app).unwrap(), "versionCode 16\nversionName \"1.3.0\"\n");
+    assert_eq!(fs::read_to_string(wear).unwrap(), "versionCode 16\nversionName \"1.3.0\"\n");
+    assert_eq!(fs::read_to_string(properties).unwrap(), "projectVersion=1.3.0\n");
+    assert!(session.check_consistency().is_ok());
+    drop(session);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn should_leave_every_file_unmodified_when_a_write_fails() {
+    let dir = env::temp_dir().join(format!("gradle-sync-session-write-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let build_gradle = dir.join("build.gradle");
+    let properties = dir.join("gradle.properties");
+    let (build_gradle, properties) = (build_gradle.to_str().unwrap(), properties.to_str().unwrap());
+    fs::write(build_gradle, "versionCode 1\nversionName \"0.1.0\"\n").unwrap();
+    fs::write(properties, "projectVersion=0.1.0\n").unwrap();
+
+    let mut session = SyncSession::new()
+        .open(build_gradle)
+        .open(properties);
+    // Syncs in memory, but can no longer be written once build.gradle is.
+    fs::remove_file(properties).unwrap();
+    fs::create_dir(properties).unwrap();
+    let result = session.sync(&Version::parse("0.2.0").unwrap());
+    assert_eq!(result.err().unwrap(), Error::IoError(format!("failed to back up '{}'", properties)));
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 1\nversionName \"0.1.0\"\n");
+    assert!(!session.files()[0].backup_path().exists());
+    drop(session);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,62 @@
+use std::env;
+use std::fs;
+use std::process;
+use semver::Version;
+
+use buildgradle::BuildGradleContent;
+use configfile::GradleFile;
+use error::Error;
+use state::VersionState;
+use version::GradleVersion;
+
+#[test]
+fn should_parse_and_record_entries() {
+    let mut state = VersionState::parse(".gradle-sync", "app/build.gradle\t1.0.0\t5\n").unwrap();
+    assert_eq!(state.version_code("app/build.gradle", &Version::parse("1.0.0").unwrap()), Some(5));
+    assert_eq!(state.version_code("gradle.properties", &Version::parse("1.0.0").unwrap()), None);
+
+    state.record("app/build.gradle", &GradleVersion::new(6, Version::parse("1.0.0").unwrap()));
+    state.record("app/build.gradle", &GradleVersion::new(7, Version::parse("1.1.0").unwrap()));
+    assert_eq!(state.render(), "app/build.gradle\t1.0.0\t6\napp/build.gradle\t1.1.0\t7\n");
+    assert_eq!(state.version_code("./app/build.gradle", &Version::parse("1.1.0").unwrap()), Some(7));
+
+    assert_eq!(VersionState::parse(".gradle-sync", "app/build.gradle 1.0.0").err().unwrap(),
+        Error::ParsingFailed("invalid entry on line 1 of '.gradle-sync'".to_string()));
+}
+
+#[test]
+fn should_reuse_version_code_after_revert() {
+    let dir = env::temp_dir().join(format!("gradle-sync-state-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let build_gradle = dir.join("build.gradle");
+    let build_gradle = build_gradle.to_str().unwrap();
+    let state = dir.join(".gradle-sync");
+    let state = state.to_str().unwrap();
+    let new_version = Version::parse("1.1.0").unwrap();
+
+    fs::write(build_gradle, "versionCode 5\nversionName \"1.0.0\"\n").unwrap();
+    GradleFile::<BuildGradleContent>::new(build_gradle).unwrap()
+        .with_state_file(state)
+        .sync_and_write(&new_version).unwrap();
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 6\nversionName \"1.1.0\"\n");
+
+    // a reverted versionName with the bumped versionCode still maps to 6
+    fs::write(build_gradle, "versionCode 6\nversionName \"1.0.0\"\n").unwrap();
+    let report = GradleFile::<BuildGradleContent>::new(build_gradle).unwrap()
+        .with_state_file(state)
+        .sync_and_write(&new_version).unwrap();
+    assert_eq!(report.new_version_code, Some(6));
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 6\nversionName \"1.1.0\"\n");
+    assert_eq!(fs::read_to_string(state).unwrap(), format!("{}\t1.1.0\t6\n", build_gradle));
+
+    // a versionCode bumped by hand is never moved back to the recorded one
+    fs::write(build_gradle, "versionCode 9\nversionName \"1.1.0\"\n").unwrap();
+    let result = GradleFile::<BuildGradleContent>::new(build_gradle).unwrap()
+        .with_state_file(state)
+        .sync_and_write(&new_version);
+    assert_eq!(result.err().unwrap(), Error::InvalidVersionCode(format!(
+        "versionCode 6 recorded for 1.1.0 in '{}' is lower than the current versionCode 9 of '{}'",
+        state, build_gradle)));
+    assert_eq!(fs::read_to_string(build_gradle).unwrap(), "versionCode 9\nversionName \"1.1.0\"\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
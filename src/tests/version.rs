@@ -1,4 +1,4 @@
-use version::GradleVersion;
+use version::{AbiSplits, GradleVersion, SemverCode, SyncAction, SyncOptions, VersionCodeStrategy, VersionPolicy, MAX_VERSION_CODE};
 use semver::Version;
 use error::Error;
 
@@ -52,3 +52,123 @@ fn test_fail_to_decrease_version_name() {
     assert_eq!(version_err, Error::VersionNotIncreasing(
             "version not increasing (old)0.2.0 > (new)0.1.2".to_string()));
 }
+
+#[test]
+fn test_warn_and_skip_keeps_version_on_decrease() {
+    let old_version_name = Version::parse("0.2.0").unwrap();
+    let new_version_name = Version::parse("0.1.2").unwrap();
+
+    let mut version = GradleVersion::new(1, old_version_name.clone());
+    let action = version.synchronize(&new_version_name, VersionPolicy::WarnAndSkip).unwrap();
+    assert_eq!(action, SyncAction::Skipped(
+            "version not increasing (old)0.2.0 > (new)0.1.2".to_string()));
+    assert!(!action.is_modified());
+    assert_eq!(version.code(), 1);
+    assert_eq!(version.version(), &old_version_name);
+}
+
+#[test]
+fn test_allow_downgrade_keeps_version_code_increasing() {
+    let new_version_name = Version::parse("0.1.2").unwrap();
+
+    let mut version = GradleVersion::new(1, Version::parse("0.2.0").unwrap());
+    let action = version.synchronize(&new_version_name, VersionPolicy::AllowDowngrade).unwrap();
+    assert!(action.is_modified());
+    assert!(action.warning().is_some());
+    assert_eq!(version.code(), 2);
+    assert_eq!(version.version(), &new_version_name);
+}
+
+#[test]
+fn test_force_exact_keeps_version_code_on_decrease() {
+    let new_version_name = Version::parse("0.1.2").unwrap();
+
+    let mut version = GradleVersion::new(4, Version::parse("0.2.0").unwrap());
+    let action = version.synchronize(&new_version_name, VersionPolicy::ForceExact).unwrap();
+    assert!(action.is_modified());
+    assert_eq!(version.code(), 4);
+    assert_eq!(version.version(), &new_version_name);
+
+    let action = version.synchronize(&Version::parse("0.3.0").unwrap(), VersionPolicy::ForceExact).unwrap();
+    assert_eq!(action, SyncAction::Upgraded);
+    assert_eq!(version.code(), 5);
+}
+
+#[test]
+fn test_semver_version_code() {
+    let strategy = SemverCode::default();
+    assert_eq!(strategy.base_code(&Version::parse("1.2.3").unwrap()), Ok(10203));
+
+    let mut version = GradleVersion::new(5, Version::parse("1.2.3").unwrap());
+    let options = SyncOptions {
+        code_strategy: VersionCodeStrategy::Semver(strategy),
+        ..SyncOptions::default()
+    };
+    let action = version.synchronize_with(&Version::parse("2.0.1").unwrap(), &options).unwrap();
+    assert_eq!(action, SyncAction::Upgraded);
+    assert_eq!(version.code(), 20001);
+}
+
+#[test]
+fn test_semver_version_code_never_decreases() {
+    let options = SyncOptions {
+        code_strategy: VersionCodeStrategy::Semver(SemverCode::default()),
+        ..SyncOptions::default()
+    };
+    let mut version = GradleVersion::new(500, Version::parse("0.1.0").unwrap());
+    let result = version.synchronize_with(&Version::parse("0.2.0").unwrap(), &options);
+    assert_eq!(result, Err(Error::InvalidVersionCode(
+        "versionCode 200 for 0.2.0 is lower than the current versionCode 500".to_string())));
+    assert_eq!(version.code(), 500);
+
+    let mut version = GradleVersion::new(10200, Version::parse("1.2.0").unwrap());
+    let options = SyncOptions { policy: VersionPolicy::AllowDowngrade, ..options };
+    let result = version.synchronize_with(&Version::parse("1.1.0").unwrap(), &options);
+    assert_eq!(result, Err(Error::InvalidVersionCode(
+        "versionCode 10100 for 1.1.0 is lower than the current versionCode 10200".to_string())));
+}
+
+#[test]
+fn test_semver_version_code_overflow() {
+    let strategy = SemverCode::default();
+    assert_eq!(strategy.base_code(&Version::parse("1.100.0").unwrap()), Err(Error::InvalidVersionCode(
+        "version 1.100.0 does not fit in 2 minor and 2 patch digits".to_string())));
+
+    let strategy = SemverCode::default().with_abi_splits(AbiSplits::new(1000).abi("x86", 1));
+    assert_eq!(strategy.base_code(&Version::parse("3000.0.0").unwrap()), Err(Error::InvalidVersionCode(
+        "versionCode for 3000.0.0 exceeds the Google Play limit of 2100000000".to_string())));
+}
+
+#[test]
+fn test_reject_version_code_above_play_limit() {
+    let mut version = GradleVersion::new(MAX_VERSION_CODE, Version::parse("0.1.0").unwrap());
+    let result = version.synchronize_version(&Version::parse("0.2.0").unwrap());
+    assert_eq!(result, Err(Error::InvalidVersionCode(
+        "versionCode 2100000001 is outside the Google Play range 1..=2100000000".to_string())));
+    assert_eq!(version.code(), MAX_VERSION_CODE);
+    assert_eq!(version.version().to_string(), "0.1.0");
+
+}
+
+#[test]
+fn test_reject_current_version_code_outside_play_range() {
+    for &code in &[0, MAX_VERSION_CODE + 1, u32::MAX] {
+        let mut version = GradleVersion::new(code, Version::parse("0.1.0").unwrap());
+        let result = version.synchronize(&Version::parse("0.1.0").unwrap(), VersionPolicy::AllowDowngrade);
+        assert_eq!(result, Err(Error::InvalidVersionCode(format!(
+            "current versionCode {} is outside the Google Play range 1..=2100000000", code))));
+        assert_eq!(version.code(), code);
+    }
+}
+
+#[test]
+fn test_reject_derived_version_code_zero() {
+    let mut version = GradleVersion::new(1, Version::parse("0.0.0-alpha").unwrap());
+    let options = SyncOptions {
+        code_strategy: VersionCodeStrategy::Semver(SemverCode::default()),
+        ..SyncOptions::default()
+    };
+    let result = version.synchronize_with(&Version::parse("0.0.0").unwrap(), &options);
+    assert_eq!(result, Err(Error::InvalidVersionCode(
+        "versionCode 0 is outside the Google Play range 1..=2100000000".to_string())));
+}
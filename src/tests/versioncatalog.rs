@@ -0,0 +1,79 @@
+use semver::Version;
+
+use versioncatalog::parse_versions_table_header;
+use versioncatalog::VersionCatalogContent;
+use configfile::ConfigurationFormat;
+use error::Error;
+
+#[test]
+fn should_recognize_table_headers() {
+    assert_eq!(parse_versions_table_header("[versions]"), Some(true));
+    assert_eq!(parse_versions_table_header("  [ versions ]"), Some(true));
+    assert_eq!(parse_versions_table_header("[libraries]"), Some(false));
+    assert_eq!(parse_versions_table_header("versionCode = \"3\""), None);
+}
+
+#[test]
+fn should_only_read_versions_table() {
+    let file_content = "
+[libraries]
+versionCode = \"99\"
+
+[versions]
+versionCode = \"3\"
+versionName = \"1.0.0\"
+".as_bytes();
+
+    let content = <VersionCatalogContent as ConfigurationFormat>::from(file_content).unwrap();
+    let version = content.current_version().unwrap();
+    assert_eq!(version.code(), 3);
+    assert_eq!(version.version().to_string(), "1.0.0");
+}
+
+#[test]
+fn should_fail_without_versions_table() {
+    let file_content = "
+[libraries]
+versionCode = 3
+versionName = \"1.0.0\"
+".as_bytes();
+
+    let content = <VersionCatalogContent as ConfigurationFormat>::from(file_content);
+    assert_eq!(content.err().unwrap(), Error::VersionNotFound(
+            "failed to find versionCode in [versions]".to_string()));
+}
+
+#[test]
+fn should_write_new_version_to_writer() {
+    let file_content = "[plugins]
+versionCode = 1
+[versions]
+versionCode = 3
+versionName = \"1.0.0\"".as_bytes();
+    let expected_file_content = "[plugins]
+versionCode = 1
+[versions]
+versionCode = 4
+versionName = \"1.1.0\"";
+
+    let mut content = <VersionCatalogContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.1.0").unwrap())
+        .expect("failed to synchronize version");
+    let mut real_content: Vec<u8> = vec!();
+    content.write(&mut real_content)
+        .expect("failed to write content to writer");
+    assert_eq!(&String::from_utf8(real_content).unwrap(), expected_file_content);
+}
+
+#[test]
+fn should_read_back_pre_release_version_name() {
+    let file_content = "[versions]\nversionCode = \"7\"\nversionName = \"1.0.0\"\n".as_bytes();
+
+    let mut content = <VersionCatalogContent as ConfigurationFormat>::from(file_content).unwrap();
+    content.sync_version(&Version::parse("1.1.0-beta.1").unwrap())
+        .expect("failed to synchronize version");
+    let rendered = content.render();
+    assert_eq!(rendered, "[versions]\nversionCode = \"8\"\nversionName = \"1.1.0-beta.1\"\n");
+    let content = <VersionCatalogContent as ConfigurationFormat>::from(rendered.as_bytes()).unwrap();
+    assert_eq!(content.current_version().unwrap().version().to_string(), "1.1.0-beta.1");
+}
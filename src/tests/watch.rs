@@ -0,0 +1,38 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use watch::Watcher;
+
+#[test]
+fn should_report_settled_changes_only_once() {
+    let path = env::temp_dir().join(format!("gradle-sync-watch-{}.toml", process::id()));
+    fs::write(&path, "version = \"0.1.0\"").unwrap();
+    let mut watcher = Watcher::new(&[&path], Duration::from_millis(20));
+    assert!(!watcher.poll());
+
+    fs::write(&path, "version = \"0.2.0\"").unwrap();
+    assert!(!watcher.poll());
+    thread::sleep(Duration::from_millis(30));
+    assert!(watcher.poll());
+    assert!(!watcher.poll());
+
+    fs::write(&path, "version = \"0.3.0\"").unwrap();
+    watcher.acknowledge(&[&path]);
+    thread::sleep(Duration::from_millis(30));
+    assert!(!watcher.poll());
+
+    // a change to a file that was not written is still reported
+    fs::write(&path, "version = \"0.4.0\"").unwrap();
+    watcher.acknowledge(&[path.with_extension("gradle")]);
+    assert!(!watcher.poll());
+    thread::sleep(Duration::from_millis(30));
+    assert!(watcher.poll());
+
+    fs::remove_file(&path).unwrap();
+    assert!(!watcher.poll());
+    thread::sleep(Duration::from_millis(30));
+    assert!(watcher.poll());
+}
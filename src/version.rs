@@ -1,11 +1,208 @@
 use semver::Version;
 use error::{Error, GradleResult};
 
+/// The highest versionCode accepted by Google Play.
+pub const MAX_VERSION_CODE: u32 = 2_100_000_000;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct GradleVersion {
     version_code: u32,
     version_name: Version
 }
 
+/// What to do when the new version is lower than the one in the file.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum VersionPolicy {
+    /// Fail with `Error::VersionNotIncreasing`.
+    #[default]
+    Error,
+    /// Keep the file untouched and report a warning.
+    WarnAndSkip,
+    /// Write the lower versionName but still increase the versionCode.
+    AllowDowngrade,
+    /// Write the exact versionName, only increasing the versionCode on upgrades.
+    ForceExact,
+}
+
+/// How the versionCode follows a new versionName.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub enum VersionCodeStrategy {
+    /// Increase the versionCode by one on every new version.
+    #[default]
+    Increment,
+    /// Derive the versionCode from the semantic version.
+    Semver(SemverCode),
+    /// Use this versionCode, e.g. the one recorded for the version in a
+    /// `VersionState`.
+    Fixed(u32),
+}
+
+/// Derives `major * 10^(minor_digits + patch_digits) + minor * 10^patch_digits + patch`
+/// as versionCode, e.g. 10203 for 1.2.3 with two digits each.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SemverCode {
+    pub minor_digits: u32,
+    pub patch_digits: u32,
+    pub abi_splits: Option<AbiSplits>,
+}
+
+/// Play Store ABI splits: every APK gets `baseCode * multiplier + abiIndex`.
+///
+/// In build.gradle the indices are kept in the `abiCodes` map and the
+/// multiplier in `abiCodeMultiplier`, both updated during a sync.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AbiSplits {
+    pub multiplier: u32,
+    pub abis: Vec<(String, u32)>,
+}
+
+impl Default for SemverCode {
+    fn default() -> SemverCode {
+        SemverCode {
+            minor_digits: 2,
+            patch_digits: 2,
+            abi_splits: None,
+        }
+    }
+}
+
+impl SemverCode {
+    pub fn with_abi_splits(mut self, abi_splits: AbiSplits) -> SemverCode {
+        self.abi_splits = Some(abi_splits);
+        self
+    }
+
+    pub fn base_code(&self, version: &Version) -> GradleResult<u32> {
+        let minor_limit = 10u64.checked_pow(self.minor_digits);
+        let patch_limit = 10u64.checked_pow(self.patch_digits);
+        let (minor_limit, patch_limit) = match (minor_limit, patch_limit) {
+            (Some(minor_limit), Some(patch_limit)) => (minor_limit, patch_limit),
+            _ => return Err(invalid_code(format!(
+                        "unsupported number of digits ({}, {})", self.minor_digits, self.patch_digits))),
+        };
+        if version.minor >= minor_limit || version.patch >= patch_limit {
+            return Err(invalid_code(format!(
+                "version {} does not fit in {} minor and {} patch digits",
+                version, self.minor_digits, self.patch_digits)))
+        }
+        let base = minor_limit.checked_mul(patch_limit)
+            .and_then(|factor| version.major.checked_mul(factor))
+            .and_then(|code| code.checked_add(version.minor * patch_limit + version.patch));
+        let highest = match (base, self.abi_splits.as_ref()) {
+            (Some(base), Some(abi_splits)) => abi_splits.highest_code(base),
+            (base, _) => base,
+        };
+        match (base, highest) {
+            (Some(base), Some(highest)) if highest <= u64::from(MAX_VERSION_CODE) => Ok(base as u32),
+            _ => Err(invalid_code(format!(
+                "versionCode for {} exceeds the Google Play limit of {}", version, MAX_VERSION_CODE))),
+        }
+    }
+}
+
+impl AbiSplits {
+    pub fn new(multiplier: u32) -> AbiSplits {
+        AbiSplits {
+            multiplier,
+            abis: vec!(),
+        }
+    }
+
+    pub fn abi(mut self, name: &str, index: u32) -> AbiSplits {
+        self.abis.push((name.to_string(), index));
+        self
+    }
+
+    pub fn index(&self, name: &str) -> Option<u32> {
+        self.abis.iter()
+            .find(|abi| abi.0 == name)
+            .map(|abi| abi.1)
+    }
+
+    fn highest_code(&self, base: u64) -> Option<u64> {
+        let highest_index = self.abis.iter().map(|abi| abi.1).max().unwrap_or(0);
+        if highest_index >= self.multiplier {
+            return None
+        }
+        base.checked_mul(u64::from(self.multiplier))
+            .and_then(|code| code.checked_add(u64::from(highest_index)))
+    }
+}
+
+impl VersionCodeStrategy {
+    /// The versionCode for `version`, or `None` when it only depends on the
+    /// previous versionCode.
+    pub fn version_code(&self, version: &Version) -> GradleResult<Option<u32>> {
+        match *self {
+            VersionCodeStrategy::Increment => Ok(None),
+            VersionCodeStrategy::Semver(ref semver_code) => semver_code.base_code(version).map(Some),
+            VersionCodeStrategy::Fixed(code) => Ok(Some(code)),
+        }
+    }
+
+    pub fn abi_splits(&self) -> Option<&AbiSplits> {
+        match *self {
+            VersionCodeStrategy::Increment | VersionCodeStrategy::Fixed(_) => None,
+            VersionCodeStrategy::Semver(ref semver_code) => semver_code.abi_splits.as_ref(),
+        }
+    }
+}
+
+/// Settings shared by every synchronization of a file.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SyncOptions {
+    pub policy: VersionPolicy,
+    pub code_strategy: VersionCodeStrategy,
+}
+
+impl SyncOptions {
+    pub fn with_policy(policy: VersionPolicy) -> SyncOptions {
+        SyncOptions {
+            policy,
+            ..SyncOptions::default()
+        }
+    }
+}
+
+/// Checks that `code` is accepted by Google Play, i.e. between 1 and
+/// `MAX_VERSION_CODE`.
+pub fn validate_version_code(code: u32) -> GradleResult<u32> {
+    if code == 0 || code > MAX_VERSION_CODE {
+        return Err(invalid_code(format!(
+            "versionCode {} is outside the Google Play range 1..={}", code, MAX_VERSION_CODE)))
+    }
+    Ok(code)
+}
+
+fn invalid_code(reason: String) -> Error {
+    Error::InvalidVersionCode(reason)
+}
+
+/// The outcome of synchronizing a `GradleVersion`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum SyncAction {
+    Unchanged,
+    Upgraded,
+    Downgraded(String),
+    Skipped(String),
+}
+
+impl SyncAction {
+    pub fn is_modified(&self) -> bool {
+        match *self {
+            SyncAction::Upgraded | SyncAction::Downgraded(_) => true,
+            SyncAction::Unchanged | SyncAction::Skipped(_) => false,
+        }
+    }
+
+    pub fn warning(&self) -> Option<&str> {
+        match *self {
+            SyncAction::Downgraded(ref warning) | SyncAction::Skipped(ref warning) => Some(warning),
+            SyncAction::Unchanged | SyncAction::Upgraded => None,
+        }
+    }
+}
+
 impl GradleVersion {
     pub fn new(version_code: u32, version_name: Version) -> Self {
         Self {
@@ -16,21 +213,83 @@ impl GradleVersion {
     pub fn version(&self) -> &Version {&self.version_name}
 
     pub fn synchronize_version(&mut self, new_version: &Version) -> GradleResult<bool> {
-        if &self.version_name > new_version {
-            let reason = format!(
-                "version not increasing (old){} > (new){}",
-                self.version_name.to_string(),
-                new_version.to_string()
-            );
-            return Err(Error::VersionNotIncreasing(reason))
+        self.synchronize(new_version, VersionPolicy::Error)
+            .map(|action| action.is_modified())
+    }
+
+    pub fn synchronize(&mut self, new_version: &Version, policy: VersionPolicy) -> GradleResult<SyncAction> {
+        self.synchronize_with(new_version, &SyncOptions::with_policy(policy))
+    }
+
+    /// The versionCode following the current one.
+    fn next_code(&self) -> GradleResult<u32> {
+        validate_version_code(self.version_code + 1)
+    }
+
+    pub fn synchronize_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        if self.version_code == 0 || self.version_code > MAX_VERSION_CODE {
+            return Err(invalid_code(format!(
+                "current versionCode {} is outside the Google Play range 1..={}",
+                self.version_code, MAX_VERSION_CODE)))
         }
+        let derived_code = match options.code_strategy.version_code(new_version)? {
+            Some(code) => Some(validate_version_code(code)?),
+            None => None,
+        };
         if &self.version_name < new_version {
-            self.version_code += 1;
+            self.version_code = match derived_code {
+                Some(code) => self.increasing_code(code, new_version)?,
+                None => self.next_code()?,
+            };
             self.version_name = new_version.clone();
-            Ok(true)
-        } else {
-            Ok(false)
+            return Ok(SyncAction::Upgraded)
+        }
+        if &self.version_name == new_version {
+            return match derived_code {
+                Some(code) if code != self.version_code => {
+                    self.version_code = match options.policy {
+                        VersionPolicy::ForceExact => code,
+                        _ => self.increasing_code(code, new_version)?,
+                    };
+                    Ok(SyncAction::Upgraded)
+                },
+                _ => Ok(SyncAction::Unchanged)
+            }
         }
+        let reason = format!(
+            "version not increasing (old){} > (new){}",
+            self.version_name,
+            new_version
+        );
+        match options.policy {
+            VersionPolicy::Error => Err(Error::VersionNotIncreasing(reason)),
+            VersionPolicy::WarnAndSkip => Ok(SyncAction::Skipped(reason)),
+            VersionPolicy::AllowDowngrade => {
+                self.version_code = match derived_code {
+                    Some(code) => self.increasing_code(code, new_version)?,
+                    None => self.next_code()?,
+                };
+                self.version_name = new_version.clone();
+                Ok(SyncAction::Downgraded(reason))
+            },
+            VersionPolicy::ForceExact => {
+                self.version_code = derived_code.unwrap_or(self.version_code);
+                self.version_name = new_version.clone();
+                Ok(SyncAction::Downgraded(reason))
+            },
+        }
+    }
+
+    /// Fails when the versionCode `code` derived for `new_version` is lower
+    /// than the current one, which Google Play would reject.
+    fn increasing_code(&self, code: u32, new_version: &Version) -> GradleResult<u32> {
+        if code < self.version_code {
+            let reason = format!(
+                "versionCode {} for {} is lower than the current versionCode {}",
+                code, new_version, self.version_code);
+            return Err(Error::InvalidVersionCode(reason))
+        }
+        Ok(code)
     }
 }
 
@@ -44,3 +303,17 @@ pub fn sem_version_parse(version_string: &str) -> GradleResult<Version> {
         Ok(version) => Ok(version)
     }
 }
+
+/// Parses a semantic version, also accepting the `major.minor` form Xcode
+/// uses by default (e.g. `1.0`) as `major.minor.0`. The flag tells whether
+/// the short form was used.
+pub(crate) fn parse_short_version(version_string: &str) -> Option<(Version, bool)> {
+    if let Ok(version) = Version::parse(version_string) {
+        return Some((version, false))
+    }
+    let parts: Vec<&str> = version_string.split('.').collect();
+    if parts.len() == 2 && parts.iter().all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit())) {
+        return Version::parse(&format!("{}.0", version_string)).ok().map(|version| (version, true))
+    }
+    None
+}
@@ -0,0 +1,106 @@
+use semver::Version;
+use regex::Regex;
+use configfile::ConfigurationFormat;
+use error::GradleResult;
+use std::io::Read;
+use std::ops::Range;
+use error::Error;
+use version::{GradleVersion, SyncAction, SyncOptions};
+use span::{VersionSpans, read_source, line_offsets, offset_range};
+
+lazy_static! {
+    static ref TABLE_HEADER_RE: Regex = Regex::new(r"^\s*\[\s*(?P<table>[^\]]+?)\s*\]").unwrap();
+    static ref VERSION_CODE_RE: Regex = Regex::new(r#"^\s*versionCode\s*=\s*"?(?P<code>\d+)"?"#).unwrap();
+    static ref VERSION_NAME_RE: Regex = Regex::new(r#"^\s*versionName\s*=\s*"(?P<name>[0-9][0-9A-Za-z.+-]*)""#).unwrap();
+}
+
+/// Gradle version catalog (`gradle/libs.versions.toml`) carrying the
+/// `versionCode` and `versionName` entries in its `[versions]` table.
+pub struct VersionCatalogContent {
+    source: String,
+    spans: VersionSpans,
+    version: GradleVersion,
+    modified: bool
+}
+
+impl ConfigurationFormat for VersionCatalogContent {
+    fn from<R: Read>(reader: R) -> GradleResult<Self>{
+        let source = read_source(reader)?;
+        let mut version_code: Option<u32> = None;
+        let mut version_name: Option<Version> = None;
+        let mut spans = VersionSpans::default();
+        let mut in_versions = false;
+
+        for (offset, line) in line_offsets(&source) {
+            if let Some(is_versions) = parse_versions_table_header(line) {
+                in_versions = is_versions;
+            } else if in_versions {
+                if let Some((code, range)) = find_catalog_version_code(line) {
+                    version_code = version_code.or(Some(code));
+                    spans.code.push(offset_range(range, offset));
+                }
+                if let Some((name, range)) = find_catalog_version_name(line) {
+                    version_name = version_name.or(Some(name));
+                    spans.name.push(offset_range(range, offset));
+                }
+            }
+        }
+        if version_code.is_none() {
+            return Err(Error::VersionNotFound("failed to find versionCode in [versions]".to_string()))
+        }
+        if version_name.is_none() {
+            return Err(Error::VersionNotFound("failed to find versionName in [versions]".to_string()))
+        }
+        Ok(Self{
+            source,
+            spans,
+            version: GradleVersion::new(
+                         version_code.unwrap(),
+                         version_name.unwrap()
+                         ),
+            modified: false
+        })
+    }
+    fn current_version(&self) -> GradleResult<&GradleVersion> {
+        Ok(&self.version)
+    }
+    fn is_modified(&self) -> bool {
+        self.modified
+    }
+    fn sync_version_with(&mut self, new_version: &Version, options: &SyncOptions) -> GradleResult<SyncAction> {
+        let action = self.version.synchronize_with(new_version, options)?;
+        if action.is_modified() {
+            self.modified = true
+        }
+        Ok(action)
+    }
+    fn render(&self) -> String {
+        self.spans.render(&self.source, &self.version)
+    }
+}
+
+/// Returns the name of the TOML table opened by the line, if any.
+pub fn parse_table_header(line: &str) -> Option<&str> {
+    TABLE_HEADER_RE.captures(line)
+        .map(|caps| caps.name("table").unwrap().as_str())
+}
+
+/// Returns `Some(true)` when the line opens the `[versions]` table,
+/// `Some(false)` for any other table header and `None` otherwise.
+pub fn parse_versions_table_header(line: &str) -> Option<bool> {
+    parse_table_header(line).map(|table| table == "versions")
+}
+
+pub fn find_catalog_version_code(line: &str) -> Option<(u32, Range<usize>)> {
+    let caps = VERSION_CODE_RE.captures(line)?;
+    let code = caps.name("code").unwrap();
+    code.as_str().parse().ok()
+        .map(|parsed| (parsed, code.start()..code.end()))
+}
+
+pub fn find_catalog_version_name(line: &str) -> Option<(Version, Range<usize>)> {
+    let caps = VERSION_NAME_RE.captures(line)?;
+    let name = caps.name("name").unwrap();
+    Version::parse(name.as_str()).ok()
+        .map(|parsed| (parsed, name.start()..name.end()))
+}
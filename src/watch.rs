@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Polls files for changes, e.g. to re-sync while `Cargo.toml` is edited.
+///
+/// Changes are reported once the files stayed the same for the debounce
+/// duration, so an editor saving in several steps only triggers one sync.
+/// Contents rather than modification times are compared, which keeps
+/// working on file systems with a coarse timestamp resolution.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    contents: Vec<Option<Vec<u8>>>,
+    debounce: Duration,
+    changed_at: Option<Instant>,
+}
+
+impl Watcher {
+    pub fn new<P: AsRef<Path>>(paths: &[P], debounce: Duration) -> Watcher {
+        let paths: Vec<PathBuf> = paths.iter().map(|path| path.as_ref().to_path_buf()).collect();
+        Watcher {
+            contents: read_all(&paths),
+            paths,
+            debounce,
+            changed_at: None,
+        }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Returns `true` once the files changed and then stayed unchanged for
+    /// the debounce duration.
+    pub fn poll(&mut self) -> bool {
+        let contents = read_all(&self.paths);
+        if contents != self.contents {
+            self.contents = contents;
+            self.changed_at = Some(Instant::now());
+            return false
+        }
+        match self.changed_at {
+            Some(changed_at) if changed_at.elapsed() >= self.debounce => {
+                self.changed_at = None;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Takes the current contents of the `written` files as unchanged. Call
+    /// this with the files a sync wrote, so those writes do not trigger
+    /// another sync while any other change made meanwhile still does.
+    pub fn acknowledge<P: AsRef<Path>>(&mut self, written: &[P]) {
+        for (path, content) in self.paths.iter().zip(self.contents.iter_mut()) {
+            if written.iter().any(|written| written.as_ref() == path.as_path()) {
+                *content = fs::read(path).ok();
+            }
+        }
+    }
+}
+
+fn read_all(paths: &[PathBuf]) -> Vec<Option<Vec<u8>>> {
+    paths.iter().map(|path| fs::read(path).ok()).collect()
+}